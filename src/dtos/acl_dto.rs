@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AclPermissionType {
+    Allow,
+    Deny,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AclActionType {
+    #[default]
+    All,
+    Publish,
+    Subscribe,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AclWeekdayType {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+/// Recurring window such as weekdays 08:00-18:00, in the timezone set by `ACL_TIMEZONE`.
+/// Times are `HH:MM`; an `end` before `start` runs past midnight.
+#[derive(Serialize, Deserialize)]
+pub struct AclScheduleDTO {
+    pub days: Vec<AclWeekdayType>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Serialize)]
+pub struct AclRuleDTO {
+    pub id: String,
+    pub permission: AclPermissionType,
+    pub topic: String,
+    pub action: AclActionType,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub qos: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<AclScheduleDTO>,
+}
+
+#[derive(Serialize)]
+pub struct GetAclRuleListDTO {
+    pub username: String,
+    pub rules: Vec<AclRuleDTO>,
+}
+
+/// Body of create and update. `position` is the zero-based index in the evaluation order;
+/// new rules are appended and updated rules keep their place when it is omitted. `qos` lists
+/// the QoS levels the rule applies to and `retain` restricts it to (non-)retained publishes.
+/// `valid_from`/`valid_until` (Unix seconds) and `schedule` limit when the rule is in effect.
+#[derive(Deserialize)]
+pub struct SaveAclRuleDTO {
+    pub permission: AclPermissionType,
+    pub topic: String,
+    #[serde(default)]
+    pub action: AclActionType,
+    #[serde(default)]
+    pub qos: Vec<u8>,
+    pub retain: Option<bool>,
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    pub schedule: Option<AclScheduleDTO>,
+    pub position: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct AclRulePathDTO {
+    pub username: String,
+    pub rule_id: String,
+}
+
+#[derive(Serialize)]
+pub struct GetAclGroupRuleListDTO {
+    pub group: String,
+    pub rules: Vec<AclRuleDTO>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateAclGroupDTO {
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct AclGroupDTO {
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Serialize)]
+pub struct GetAclGroupListDTO {
+    pub groups: Vec<AclGroupDTO>,
+}
+
+/// The user's groups in evaluation order; `PUT` replaces the whole list.
+#[derive(Serialize, Deserialize)]
+pub struct UserGroupsDTO {
+    pub groups: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AclGroupPathDTO {
+    pub group: String,
+}
+
+#[derive(Deserialize)]
+pub struct AclGroupRulePathDTO {
+    pub group: String,
+    pub rule_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct UserGroupPathDTO {
+    pub username: String,
+    pub group: String,
+}
+
+/// Result of an ACL dry run: the decision, what produced it, and every rule looked at on
+/// the way, in evaluation order.
+#[derive(Serialize)]
+pub struct AclExplanationDTO {
+    pub result: &'static str,
+    pub source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<AclRuleDTO>,
+    /// Share group of a `$share`/`$queue` subscription; rules were matched against the filter inside it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_group: Option<String>,
+    pub evaluated_rules: Vec<EvaluatedAclRuleDTO>,
+}
+
+#[derive(Serialize)]
+pub struct EvaluatedAclRuleDTO {
+    pub source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub rule: AclRuleDTO,
+    /// The topic filter after placeholder expansion, when it could be expanded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded_topic: Option<String>,
+    pub matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+pub struct AclCacheStatsDTO {
+    pub enabled: bool,
+    pub entries: usize,
+    pub capacity: usize,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
@@ -1,9 +1,32 @@
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub username: String,
     pub exp: usize,
     pub iat: usize,
+    /// Issue time in Unix milliseconds; `iat` has whole seconds only, too coarse to tell apart
+    /// tokens issued just before and just after a revoke-all.
+    pub iat_ms: i64,
+    pub nbf: usize,
     pub sub: String,
+    pub jti: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acl: Option<AclClaim>,
+}
+
+/// EMQX JWT authorization claim: topic filters the client may publish to,
+/// subscribe to, or both.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AclClaim {
+    #[serde(rename = "pub", default, skip_serializing_if = "Vec::is_empty")]
+    pub publish: Vec<String>,
+    #[serde(rename = "sub", default, skip_serializing_if = "Vec::is_empty")]
+    pub subscribe: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub all: Vec<String>,
 }
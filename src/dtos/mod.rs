@@ -1,3 +1,5 @@
 pub mod mqtt_dto;
 pub mod response_dto;
-pub mod jwt_dto;
\ No newline at end of file
+pub mod jwt_dto;
+pub mod acl_dto;
+pub mod presence_dto;
\ No newline at end of file
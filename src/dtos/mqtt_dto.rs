@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize};
 
 #[derive(Serialize)]
 pub struct MqttDTO {
@@ -25,11 +27,31 @@ pub struct MqttLoginDTO {
     pub username: String,
     pub password: String,
     pub method: Option<AuthType>,
+    /// Requested token lifetime in seconds for `jwt`; capped by `JWT_MAX_TTL_SECONDS`.
+    #[serde(default, deserialize_with = "deserialize_lenient_number")]
+    pub ttl: Option<u64>,
+    /// Client ID EMQX is authenticating; checked against the user's allowed client IDs.
+    pub clientid: Option<String>,
+    /// Address EMQX saw the client connect from; checked against the user's allowed networks.
+    pub peerhost: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct MqttJwtDTO {
     pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+/// Outcome of a successful `/mqtt/check`: the user's superuser flag and, for `jwt`, the issued tokens.
+pub struct MqttLoginResultDTO {
+    pub is_superuser: bool,
+    pub jwt: Option<MqttJwtDTO>,
+}
+
+#[derive(Deserialize)]
+pub struct RefreshTokenDTO {
+    pub refresh_token: String,
 }
 
 #[derive(Deserialize, PartialEq)]
@@ -37,15 +59,165 @@ pub struct MqttJwtDTO {
 pub enum AuthType {
     Credentials,
     Jwt,
+    /// The password field carries a JWT previously issued through `Jwt`.
+    #[serde(rename = "jwt_verify")]
+    JwtVerify,
 }
 
+/// EMQX HTTP authorization request. EMQX renders `${qos}` and `${retain}` placeholders as
+/// strings, and query and form requests carry only strings, so both strings and native JSON
+/// values are accepted.
 #[derive(Deserialize)]
 pub struct MqttAclDTO {
     pub username: String,
     pub topic: String,
+    pub clientid: Option<String>,
+    pub peerhost: Option<String>,
+    pub action: Option<MqttAclAction>,
+    #[serde(default, deserialize_with = "deserialize_lenient_number")]
+    pub qos: Option<u8>,
+    #[serde(default, deserialize_with = "deserialize_lenient_bool")]
+    pub retain: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MqttAclAction {
+    Publish,
+    Subscribe,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Lenient<T> {
+    Value(T),
+    Text(String),
 }
 
+fn deserialize_lenient_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    match Option::<Lenient<T>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Lenient::Value(v)) => Ok(Some(v)),
+        Some(Lenient::Text(t)) if t.is_empty() => Ok(None),
+        Some(Lenient::Text(t)) => t.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+fn deserialize_lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    match Option::<Lenient<bool>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Lenient::Value(v)) => Ok(Some(v)),
+        Some(Lenient::Text(t)) if t.is_empty() => Ok(None),
+        Some(Lenient::Text(t)) => match t.as_str() {
+            "true" | "1" => Ok(Some(true)),
+            "false" | "0" => Ok(Some(false)),
+            _ => Err(de::Error::custom(format!("invalid retain flag `{}`", t))),
+        },
+    }
+}
+
+/// `{username}` path segment of the per-user endpoints.
 #[derive(Deserialize)]
-pub struct DeleteMqttDTO {
+pub struct UsernamePathDTO {
     pub username: String,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct RevokeTokenDTO {
+    pub token: String,
+}
+
+/// RFC 7662 request, usually form-encoded. Only access tokens are issued, so the hint is advisory.
+#[derive(Deserialize)]
+pub struct IntrospectTokenDTO {
+    #[serde(default)]
+    pub token: String,
+    pub token_type_hint: Option<String>,
+}
+
+/// RFC 7662 introspection response. Only `active` is set for inactive tokens; the remaining
+/// fields describe an active token and its user as they are now.
+#[derive(Serialize, Default)]
+pub struct TokenIntrospectionDTO {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_superuser: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct RotateJwtKeyDTO {
+    pub kid: Option<String>,
+    pub algorithm: String,
+    pub secret: Option<String>,
+    pub private_key: Option<String>,
+    pub public_key: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct JwtKeyDTO {
+    pub kid: String,
+    pub algorithm: String,
+    pub is_active: bool,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_until: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct GetJwtKeyListDTO {
+    pub keys: Vec<JwtKeyDTO>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientIdMatchType {
+    Exact,
+    Glob,
+    Regex,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClientIdPatternDTO {
+    #[serde(rename = "type")]
+    pub match_type: ClientIdMatchType,
+    pub pattern: String,
+}
+
+/// Client IDs a user may connect with; `PUT` replaces the whole list. With no patterns and
+/// `bind_first` set, the first client ID that logs in is stored as an exact pattern.
+#[derive(Serialize, Deserialize)]
+pub struct MqttClientIdsDTO {
+    pub patterns: Vec<ClientIdPatternDTO>,
+    #[serde(default)]
+    pub bind_first: bool,
+}
+
+/// Networks, in CIDR notation, a user may connect from; `PUT` replaces the whole list and an
+/// empty list allows any address.
+#[derive(Serialize, Deserialize)]
+pub struct MqttNetworksDTO {
+    pub networks: Vec<String>,
+}
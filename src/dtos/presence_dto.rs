@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Client event forwarded by an EMQX webhook or rule-engine action. Fields other than these are
+/// ignored, so the full event payload can be sent as is.
+#[derive(Deserialize)]
+pub struct MqttEventDTO {
+    /// `client.connected`, `client.disconnected` or `session.terminated`.
+    #[serde(alias = "action")]
+    pub event: String,
+    pub username: Option<String>,
+    pub clientid: Option<String>,
+    #[serde(alias = "ipaddress")]
+    pub peername: Option<String>,
+    pub proto_ver: Option<u8>,
+    pub reason: Option<String>,
+    /// Unix milliseconds from EMQX 5, Unix seconds from EMQX 4 webhooks.
+    pub connected_at: Option<i64>,
+    pub disconnected_at: Option<i64>,
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct PresenceDTO {
+    pub username: String,
+    pub online: bool,
+    pub clientid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proto_ver: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connected_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disconnected_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disconnect_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_terminated_at: Option<i64>,
+    pub last_seen: i64,
+}
+
+#[derive(Serialize)]
+pub struct GetPresenceListDTO {
+    pub users: Vec<PresenceDTO>,
+}
+
+#[derive(Deserialize)]
+pub struct OfflinePresenceQueryDTO {
+    pub hours: Option<u64>,
+}
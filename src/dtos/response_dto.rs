@@ -24,4 +24,36 @@ where
     pub result: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<D>,
-}
\ No newline at end of file
+}
+
+/// How `/mqtt/check` and `/mqtt/acl` answer: the `ResponseDTO` envelope with error status
+/// codes, or EMQX's native HTTP authenticator/authorizer body with status 200.
+#[derive(Clone, Copy, PartialEq)]
+pub enum AuthResponseFormat {
+    Envelope,
+    Emqx,
+}
+
+impl AuthResponseFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "envelope" => Some(Self::Envelope),
+            "emqx" => Some(Self::Emqx),
+            _ => None,
+        }
+    }
+}
+
+/// EMQX's native HTTP auth response. `is_superuser` is only sent by the authenticator, and
+/// the `jwt` method's tokens are added alongside for callers that request them.
+#[derive(Serialize)]
+pub struct EmqxResponseDTO<T = ()>
+where
+    T: Serialize,
+{
+    pub result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_superuser: Option<bool>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+}
@@ -0,0 +1,24 @@
+use bincode::{Encode, Decode};
+
+/// A named policy shared by many users. Its rules are stored alongside, under the group name.
+#[derive(Encode, Decode, Clone)]
+pub struct AclGroupEntity {
+    pub name: String,
+    pub created_at: i64,
+}
+
+impl AclGroupEntity {
+    pub fn create(name: impl Into<String>, created_at: i64) -> Self {
+        AclGroupEntity {
+            name: name.into(),
+            created_at,
+        }
+    }
+}
+
+/// The groups a user belongs to, in the order their rules are evaluated.
+#[derive(Encode, Decode, Clone, Default)]
+pub struct UserGroupsEntity {
+    pub username: String,
+    pub groups: Vec<String>,
+}
@@ -0,0 +1,66 @@
+use bincode::{Encode, Decode};
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq)]
+pub enum AclPermission {
+    Allow,
+    Deny,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq)]
+pub enum AclAction {
+    All,
+    Publish,
+    Subscribe,
+}
+
+/// Recurring window on the listed weekdays (0 = Monday), from `start` to `end` in minutes
+/// after midnight. When `end` is not after `start` the window runs past midnight.
+#[derive(Encode, Decode, Clone)]
+pub struct AclScheduleEntity {
+    pub days: Vec<u8>,
+    pub start: u16,
+    pub end: u16,
+}
+
+/// When a rule is in effect. Timestamps are Unix seconds; `valid_until` is exclusive.
+#[derive(Encode, Decode, Clone, Default)]
+pub struct AclValidityEntity {
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    pub schedule: Option<AclScheduleEntity>,
+}
+
+/// One entry of a user's ordered ACL. The first rule whose `topic` filter matches and whose
+/// conditions hold decides. An empty `qos` list and a `None` retain flag match anything.
+#[derive(Encode, Decode, Clone)]
+pub struct AclRuleEntity {
+    pub id: String,
+    pub permission: AclPermission,
+    pub topic: String,
+    pub action: AclAction,
+    pub qos: Vec<u8>,
+    pub retain: Option<bool>,
+    pub validity: AclValidityEntity,
+}
+
+impl AclRuleEntity {
+    pub fn create(
+        id: impl Into<String>,
+        permission: AclPermission,
+        topic: impl Into<String>,
+        action: AclAction,
+        qos: Vec<u8>,
+        retain: Option<bool>,
+        validity: AclValidityEntity,
+    ) -> Self {
+        AclRuleEntity {
+            id: id.into(),
+            permission,
+            topic: topic.into(),
+            action,
+            qos,
+            retain,
+            validity,
+        }
+    }
+}
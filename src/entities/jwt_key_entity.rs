@@ -0,0 +1,36 @@
+use bincode::{Encode, Decode};
+
+/// Persisted signing key material. Retired keys stay verifiable until `verify_until`.
+#[derive(Encode, Decode, Clone)]
+pub struct JwtKeyEntity {
+    pub kid: String,
+    pub algorithm: String,
+    pub secret: Option<String>,
+    pub private_key_pem: Option<String>,
+    pub public_key_pem: Option<String>,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub verify_until: Option<i64>,
+}
+
+impl JwtKeyEntity {
+    pub fn create(
+        kid: impl Into<String>,
+        algorithm: impl Into<String>,
+        secret: Option<String>,
+        private_key_pem: Option<String>,
+        public_key_pem: Option<String>,
+        created_at: i64,
+    ) -> Self {
+        JwtKeyEntity {
+            kid: kid.into(),
+            algorithm: algorithm.into(),
+            secret,
+            private_key_pem,
+            public_key_pem,
+            is_active: true,
+            created_at,
+            verify_until: None,
+        }
+    }
+}
@@ -1 +1,7 @@
 pub mod mqtt_entity;
+pub mod refresh_token_entity;
+pub mod revoked_token_entity;
+pub mod jwt_key_entity;
+pub mod acl_rule_entity;
+pub mod acl_group_entity;
+pub mod presence_entity;
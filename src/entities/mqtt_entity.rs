@@ -1,11 +1,27 @@
 use bincode::{Encode, Decode};
+use bincode::de::Decoder;
+use bincode::error::DecodeError;
 
-#[derive(Encode, Decode)]
+/// Client ID a user may connect with. Globs support `*` and `?`; regexes must match the whole ID.
+#[derive(Encode, Decode, Clone)]
+pub enum ClientIdPatternEntity {
+    Exact(String),
+    Glob(String),
+    Regex(String),
+}
+
+#[derive(Encode)]
 pub struct MqttEntity {
     pub username: String,
     pub password: String,
     pub is_deleted: bool,
     pub is_superuser: bool,
+    /// Allowed client IDs; empty allows any.
+    pub client_ids: Vec<ClientIdPatternEntity>,
+    /// Bind the first client ID seen at login when `client_ids` is empty.
+    pub bind_client_id: bool,
+    /// Networks, in CIDR notation, the user may connect from; empty allows any.
+    pub allowed_networks: Vec<String>,
 }
 
 impl MqttEntity {
@@ -15,6 +31,87 @@ impl MqttEntity {
             password: password.into(),
             is_deleted: false,
             is_superuser: is_superuser.into(),
+            client_ids: Vec::new(),
+            bind_client_id: false,
+            allowed_networks: Vec::new(),
         }
     }
 }
+
+// Fields after `is_superuser` were added later; records written before them end early and
+// decode with the defaults.
+impl<Context> Decode<Context> for MqttEntity {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(MqttEntity {
+            username: Decode::decode(decoder)?,
+            password: Decode::decode(decoder)?,
+            is_deleted: Decode::decode(decoder)?,
+            is_superuser: Decode::decode(decoder)?,
+            client_ids: decode_or_default(decoder)?,
+            bind_client_id: decode_or_default(decoder)?,
+            allowed_networks: decode_or_default(decoder)?,
+        })
+    }
+}
+
+bincode::impl_borrow_decode!(MqttEntity);
+
+fn decode_or_default<Context, D, T>(decoder: &mut D) -> Result<T, DecodeError>
+where
+    D: Decoder<Context = Context>,
+    T: Decode<Context> + Default,
+{
+    match T::decode(decoder) {
+        Err(DecodeError::UnexpectedEnd { .. }) => Ok(T::default()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::config::standard;
+
+    // Layout of records written before client IDs and networks were added.
+    #[derive(Encode)]
+    struct LegacyMqttEntity {
+        username: String,
+        password: String,
+        is_deleted: bool,
+        is_superuser: bool,
+    }
+
+    #[test]
+    fn decodes_legacy_record_with_defaults() {
+        let legacy = LegacyMqttEntity {
+            username: "sensor_001".to_string(),
+            password: "hash".to_string(),
+            is_deleted: false,
+            is_superuser: true,
+        };
+        let bytes = bincode::encode_to_vec(&legacy, standard()).unwrap();
+
+        let (entity, _): (MqttEntity, usize) = bincode::decode_from_slice(&bytes, standard()).unwrap();
+        assert_eq!(entity.username, "sensor_001");
+        assert_eq!(entity.password, "hash");
+        assert!(!entity.is_deleted);
+        assert!(entity.is_superuser);
+        assert!(entity.client_ids.is_empty());
+        assert!(!entity.bind_client_id);
+        assert!(entity.allowed_networks.is_empty());
+    }
+
+    #[test]
+    fn round_trips_current_record() {
+        let mut entity = MqttEntity::create("sensor_001", "hash", false);
+        entity.client_ids = vec![ClientIdPatternEntity::Glob("sensor-*".to_string())];
+        entity.bind_client_id = true;
+        entity.allowed_networks = vec!["10.0.0.0/8".to_string()];
+        let bytes = bincode::encode_to_vec(&entity, standard()).unwrap();
+
+        let (decoded, _): (MqttEntity, usize) = bincode::decode_from_slice(&bytes, standard()).unwrap();
+        assert!(matches!(decoded.client_ids.as_slice(), [ClientIdPatternEntity::Glob(p)] if p == "sensor-*"));
+        assert!(decoded.bind_client_id);
+        assert_eq!(decoded.allowed_networks, vec!["10.0.0.0/8".to_string()]);
+    }
+}
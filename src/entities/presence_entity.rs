@@ -0,0 +1,35 @@
+use bincode::{Encode, Decode};
+
+/// Last known connection state of a user, built from EMQX client events. Timestamps are Unix
+/// seconds.
+#[derive(Encode, Decode, Clone)]
+pub struct PresenceEntity {
+    pub username: String,
+    pub online: bool,
+    pub clientid: String,
+    pub peer: Option<String>,
+    pub proto_ver: Option<u8>,
+    pub connected_at: Option<i64>,
+    pub disconnected_at: Option<i64>,
+    pub disconnect_reason: Option<String>,
+    pub session_terminated_at: Option<i64>,
+    /// Time of the latest event about this user.
+    pub last_seen: i64,
+}
+
+impl PresenceEntity {
+    pub fn create(username: impl Into<String>, clientid: impl Into<String>, last_seen: i64) -> Self {
+        PresenceEntity {
+            username: username.into(),
+            online: false,
+            clientid: clientid.into(),
+            peer: None,
+            proto_ver: None,
+            connected_at: None,
+            disconnected_at: None,
+            disconnect_reason: None,
+            session_terminated_at: None,
+            last_seen,
+        }
+    }
+}
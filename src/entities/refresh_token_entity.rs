@@ -0,0 +1,44 @@
+use bincode::{Encode, Decode};
+
+/// A refresh token, stored under the SHA-256 of the opaque value handed to the client.
+#[derive(Encode, Decode)]
+pub struct RefreshTokenEntity {
+    pub token_hash: String,
+    pub username: String,
+    pub family_id: String,
+    pub issued_at_ms: i64,
+    pub expires_at: i64,
+    pub is_used: bool,
+}
+
+/// All refresh tokens descending from one login share a family; reusing a rotated
+/// token revokes the whole family.
+#[derive(Encode, Decode)]
+pub struct RefreshFamilyEntity {
+    pub family_id: String,
+    pub username: String,
+    pub is_revoked: bool,
+}
+
+impl RefreshTokenEntity {
+    pub fn create(token_hash: impl Into<String>, username: impl Into<String>, family_id: impl Into<String>, issued_at_ms: i64, expires_at: i64) -> Self {
+        RefreshTokenEntity {
+            token_hash: token_hash.into(),
+            username: username.into(),
+            family_id: family_id.into(),
+            issued_at_ms,
+            expires_at,
+            is_used: false,
+        }
+    }
+}
+
+impl RefreshFamilyEntity {
+    pub fn create(family_id: impl Into<String>, username: impl Into<String>) -> Self {
+        RefreshFamilyEntity {
+            family_id: family_id.into(),
+            username: username.into(),
+            is_revoked: false,
+        }
+    }
+}
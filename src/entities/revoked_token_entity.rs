@@ -0,0 +1,35 @@
+use bincode::{Encode, Decode};
+
+/// A single revoked access token, kept until the token would have expired anyway.
+#[derive(Encode, Decode)]
+pub struct RevokedTokenEntity {
+    pub jti: String,
+    pub username: String,
+    pub expires_at: i64,
+}
+
+/// Every token of `username` issued before `revoked_before_ms` is revoked.
+#[derive(Encode, Decode)]
+pub struct UserTokenRevocationEntity {
+    pub username: String,
+    pub revoked_before_ms: i64,
+}
+
+impl RevokedTokenEntity {
+    pub fn create(jti: impl Into<String>, username: impl Into<String>, expires_at: i64) -> Self {
+        RevokedTokenEntity {
+            jti: jti.into(),
+            username: username.into(),
+            expires_at,
+        }
+    }
+}
+
+impl UserTokenRevocationEntity {
+    pub fn create(username: impl Into<String>, revoked_before_ms: i64) -> Self {
+        UserTokenRevocationEntity {
+            username: username.into(),
+            revoked_before_ms,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::acl_group_service::AclGroupService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::acl_dto::{AclGroupPathDTO, CreateAclGroupDTO, GetAclGroupListDTO, UserGroupPathDTO, UserGroupsDTO};
+use crate::dtos::mqtt_dto::UsernamePathDTO;
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub acl_group_service: Arc<AclGroupService>,
+}
+
+pub async fn create_acl_group_handler(
+    data: web::Data<AppState>,
+    body: web::Json<CreateAclGroupDTO>,
+) -> impl Responder {
+    match data.acl_group_service.create_group(body.into_inner()) {
+        Ok(group) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL group created successfully",
+            data: Some(group),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn get_acl_group_list_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match data.acl_group_service.get_group_list() {
+        Ok(groups) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL group list retrieved successfully",
+            data: Some(GetAclGroupListDTO { groups }),
+            result: None,
+        }),
+        Err(e) => e.to_http_response(),
+    }
+}
+
+pub async fn delete_acl_group_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclGroupPathDTO>,
+) -> impl Responder {
+    match data.acl_group_service.delete_group(&params.group) {
+        Ok(_) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: "ACL group deleted successfully",
+            data: None,
+            result: None,
+        }),
+        Err(e) => e.to_http_response_with_details(None::<String>),
+    }
+}
+
+pub async fn get_user_groups_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    user_groups_response(
+        data.acl_group_service.get_user_groups(&params.username),
+        "User MQTT groups retrieved successfully",
+    )
+}
+
+pub async fn set_user_groups_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+    body: web::Json<UserGroupsDTO>,
+) -> impl Responder {
+    user_groups_response(
+        data.acl_group_service.set_user_groups(&params.username, body.into_inner()),
+        "User MQTT groups updated successfully",
+    )
+}
+
+pub async fn add_user_group_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UserGroupPathDTO>,
+) -> impl Responder {
+    user_groups_response(
+        data.acl_group_service.add_user_group(&params.username, &params.group),
+        "User MQTT added to ACL group successfully",
+    )
+}
+
+pub async fn remove_user_group_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UserGroupPathDTO>,
+) -> impl Responder {
+    user_groups_response(
+        data.acl_group_service.remove_user_group(&params.username, &params.group),
+        "User MQTT removed from ACL group successfully",
+    )
+}
+
+fn user_groups_response(result: Result<Vec<String>, MqttServiceError>, message: &'static str) -> HttpResponse {
+    match result {
+        Ok(groups) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message,
+            data: Some(UserGroupsDTO { groups }),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
@@ -0,0 +1,180 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::acl_rule_service::{AclRuleOwner, AclRuleService};
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::acl_dto::{
+    AclGroupPathDTO, AclGroupRulePathDTO, AclRulePathDTO, GetAclGroupRuleListDTO,
+    GetAclRuleListDTO, SaveAclRuleDTO,
+};
+use crate::dtos::mqtt_dto::UsernamePathDTO;
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub acl_rule_service: Arc<AclRuleService>,
+}
+
+pub async fn get_acl_rule_list_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.get_rule_list(AclRuleOwner::User(&params.username)) {
+        Ok(rules) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule list retrieved successfully",
+            data: Some(GetAclRuleListDTO { username: params.into_inner().username, rules }),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn create_acl_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+    body: web::Json<SaveAclRuleDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.create_rule(AclRuleOwner::User(&params.username), body.into_inner()) {
+        Ok(rule) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule created successfully",
+            data: Some(rule),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn update_acl_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclRulePathDTO>,
+    body: web::Json<SaveAclRuleDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.update_rule(AclRuleOwner::User(&params.username), &params.rule_id, body.into_inner()) {
+        Ok(rule) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule updated successfully",
+            data: Some(rule),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn delete_acl_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclRulePathDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.delete_rule(AclRuleOwner::User(&params.username), &params.rule_id) {
+        Ok(_) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: "ACL rule deleted successfully",
+            data: None,
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn get_acl_group_rule_list_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclGroupPathDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.get_rule_list(AclRuleOwner::Group(&params.group)) {
+        Ok(rules) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule list retrieved successfully",
+            data: Some(GetAclGroupRuleListDTO { group: params.into_inner().group, rules }),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn create_acl_group_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclGroupPathDTO>,
+    body: web::Json<SaveAclRuleDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.create_rule(AclRuleOwner::Group(&params.group), body.into_inner()) {
+        Ok(rule) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule created successfully",
+            data: Some(rule),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn update_acl_group_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclGroupRulePathDTO>,
+    body: web::Json<SaveAclRuleDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.update_rule(AclRuleOwner::Group(&params.group), &params.rule_id, body.into_inner()) {
+        Ok(rule) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL rule updated successfully",
+            data: Some(rule),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn delete_acl_group_rule_handler(
+    data: web::Data<AppState>,
+    params: web::Path<AclGroupRulePathDTO>,
+) -> impl Responder {
+    match data.acl_rule_service.delete_rule(AclRuleOwner::Group(&params.group), &params.rule_id) {
+        Ok(_) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: "ACL rule deleted successfully",
+            data: None,
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
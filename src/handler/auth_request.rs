@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::services::service_error::{MqttServiceError, ValidationError};
+
+/// DTO fields an incoming field name may be mapped to.
+const MAPPABLE_FIELDS: [&str; 10] = [
+    "username", "password", "method", "ttl", "clientid", "peerhost", "topic", "action", "qos", "retain",
+];
+
+/// Renames incoming request fields to DTO fields, for EMQX templates that use other names.
+#[derive(Default)]
+pub struct FieldMapping(HashMap<String, String>);
+
+impl FieldMapping {
+    /// Parse `incoming=field` pairs separated by commas, e.g. `user=username,pass=password`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut mapping = HashMap::new();
+        for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((incoming, field)) = pair.split_once('=') else {
+                return Err(format!("`{}` is not an incoming=field pair", pair));
+            };
+            let (incoming, field) = (incoming.trim(), field.trim());
+            if incoming.is_empty() || !MAPPABLE_FIELDS.contains(&field) {
+                return Err(format!("`{}` does not map to one of {}", pair, MAPPABLE_FIELDS.join(", ")));
+            }
+            mapping.insert(incoming.to_string(), field.to_string());
+        }
+        Ok(FieldMapping(mapping))
+    }
+
+    fn field<'a>(&'a self, incoming: &'a str) -> &'a str {
+        self.0.get(incoming).map_or(incoming, String::as_str)
+    }
+}
+
+/// Body of the EMQX-facing endpoints, taken from the query string, a form-encoded body or a
+/// JSON body; body fields win over query parameters of the same name. Decoding never rejects
+/// the request, so handlers answer malformed input in their own response format.
+pub struct AuthRequest<T>(Result<T, MqttServiceError>);
+
+impl<T> AuthRequest<T> {
+    pub fn into_inner(self) -> Result<T, MqttServiceError> {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for AuthRequest<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        let body = web::Bytes::from_request(&req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            let default = FieldMapping::default();
+            let mapping = req.app_data::<web::Data<FieldMapping>>().map_or(&default, |m| m.as_ref());
+            Ok(AuthRequest(decode(&req, &body, mapping)))
+        })
+    }
+}
+
+fn decode<T: DeserializeOwned>(req: &HttpRequest, body: &[u8], mapping: &FieldMapping) -> Result<T, MqttServiceError> {
+    let mut fields = Map::new();
+    for (name, value) in parse_urlencoded(req.query_string())? {
+        fields.insert(mapping.field(&name).to_string(), Value::String(value));
+    }
+
+    if !body.is_empty() {
+        let body_fields = if req.content_type() == "application/x-www-form-urlencoded" {
+            let body = std::str::from_utf8(body).map_err(|e| invalid_request(e.to_string()))?;
+            parse_urlencoded(body)?.into_iter().map(|(name, value)| (name, Value::String(value))).collect()
+        } else {
+            serde_json::from_slice::<Map<String, Value>>(body).map_err(|e| invalid_request(e.to_string()))?
+        };
+        for (name, value) in body_fields {
+            fields.insert(mapping.field(&name).to_string(), value);
+        }
+    }
+
+    debug!("[Handler | AuthRequest] Decoded {} request fields.", fields.len());
+    serde_json::from_value(Value::Object(fields)).map_err(|e| invalid_request(e.to_string()))
+}
+
+fn parse_urlencoded(input: &str) -> Result<Vec<(String, String)>, MqttServiceError> {
+    web::Query::<Vec<(String, String)>>::from_query(input)
+        .map(web::Query::into_inner)
+        .map_err(|e| invalid_request(e.to_string()))
+}
+
+fn invalid_request(message: String) -> MqttServiceError {
+    MqttServiceError::BadRequest(vec![ValidationError {
+        field: "body".to_string(),
+        message,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use crate::dtos::mqtt_dto::{MqttAclAction, MqttAclDTO, MqttLoginDTO};
+
+    const FORM: &str = "application/x-www-form-urlencoded";
+    const JSON: &str = "application/json";
+
+    fn request(uri: &str, content_type: &str) -> HttpRequest {
+        TestRequest::with_uri(uri).insert_header(("content-type", content_type)).to_http_request()
+    }
+
+    #[test]
+    fn parse_mapping_pairs() {
+        let mapping = FieldMapping::parse(" user = username ,pass=password,").unwrap();
+        assert_eq!(mapping.field("user"), "username");
+        assert_eq!(mapping.field("pass"), "password");
+        assert_eq!(mapping.field("topic"), "topic");
+        assert!(FieldMapping::parse("").unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn parse_mapping_rejects_malformed_values() {
+        assert!(FieldMapping::parse("user").is_err());
+        assert!(FieldMapping::parse("user=login").is_err());
+        assert!(FieldMapping::parse("=username").is_err());
+        assert!(FieldMapping::parse("user=username,pass").is_err());
+    }
+
+    #[test]
+    fn decode_from_query() {
+        let req = request("/mqtt/acl?username=alice&topic=a%2Fb&action=publish&qos=1&retain=true", JSON);
+        let dto: MqttAclDTO = decode(&req, b"", &FieldMapping::default()).unwrap();
+        assert_eq!(dto.username, "alice");
+        assert_eq!(dto.topic, "a/b");
+        assert!(dto.action == Some(MqttAclAction::Publish));
+        assert_eq!(dto.qos, Some(1));
+        assert_eq!(dto.retain, Some(true));
+    }
+
+    #[test]
+    fn decode_from_form() {
+        let req = request("/mqtt/login", FORM);
+        let dto: MqttLoginDTO = decode(&req, b"username=alice&password=s3cret&ttl=60", &FieldMapping::default()).unwrap();
+        assert_eq!(dto.username, "alice");
+        assert_eq!(dto.password, "s3cret");
+        assert_eq!(dto.ttl, Some(60));
+    }
+
+    #[test]
+    fn decode_from_json_with_string_encoded_values() {
+        let req = request("/mqtt/acl", JSON);
+        let body = br#"{"username":"alice","topic":"a/b","qos":"2","retain":"false"}"#;
+        let dto: MqttAclDTO = decode(&req, body, &FieldMapping::default()).unwrap();
+        assert_eq!(dto.qos, Some(2));
+        assert_eq!(dto.retain, Some(false));
+
+        let body = br#"{"username":"alice","topic":"a/b","qos":0,"retain":true}"#;
+        let dto: MqttAclDTO = decode(&req, body, &FieldMapping::default()).unwrap();
+        assert_eq!(dto.qos, Some(0));
+        assert_eq!(dto.retain, Some(true));
+    }
+
+    #[test]
+    fn body_overrides_query() {
+        let req = request("/mqtt/acl?username=mallory&topic=a/b", JSON);
+        let dto: MqttAclDTO = decode(&req, br#"{"username":"alice"}"#, &FieldMapping::default()).unwrap();
+        assert_eq!(dto.username, "alice");
+        assert_eq!(dto.topic, "a/b");
+
+        let req = request("/mqtt/acl?username=mallory&topic=a/b", FORM);
+        let dto: MqttAclDTO = decode(&req, b"username=alice", &FieldMapping::default()).unwrap();
+        assert_eq!(dto.username, "alice");
+    }
+
+    #[test]
+    fn decode_renames_mapped_fields() {
+        let mapping = FieldMapping::parse("user=username,pass=password").unwrap();
+        let req = request("/mqtt/login?user=alice", FORM);
+        let dto: MqttLoginDTO = decode(&req, b"pass=s3cret", &mapping).unwrap();
+        assert_eq!(dto.username, "alice");
+        assert_eq!(dto.password, "s3cret");
+    }
+
+    #[test]
+    fn decode_reports_malformed_input() {
+        let req = request("/mqtt/acl", JSON);
+        let result = decode::<MqttAclDTO>(&req, b"{not json", &FieldMapping::default());
+        assert!(matches!(result, Err(MqttServiceError::BadRequest(_))));
+
+        let result = decode::<MqttAclDTO>(&req, br#"{"username":"alice"}"#, &FieldMapping::default());
+        assert!(matches!(result, Err(MqttServiceError::BadRequest(_))));
+
+        let result = decode::<MqttAclDTO>(&req, br#"{"username":"alice","topic":"a","qos":"high"}"#, &FieldMapping::default());
+        assert!(matches!(result, Err(MqttServiceError::BadRequest(_))));
+    }
+}
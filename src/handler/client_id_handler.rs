@@ -0,0 +1,51 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::client_id_service::ClientIdService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::UsernamePathDTO;
+use crate::dtos::mqtt_dto::MqttClientIdsDTO;
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub client_id_service: Arc<ClientIdService>,
+}
+
+pub async fn get_client_ids_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    client_ids_response(
+        data.client_id_service.get_client_ids(&params.username),
+        "User MQTT client IDs retrieved successfully",
+    )
+}
+
+pub async fn set_client_ids_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+    body: web::Json<MqttClientIdsDTO>,
+) -> impl Responder {
+    client_ids_response(
+        data.client_id_service.set_client_ids(&params.username, body.into_inner()),
+        "User MQTT client IDs updated successfully",
+    )
+}
+
+fn client_ids_response(result: Result<MqttClientIdsDTO, MqttServiceError>, message: &'static str) -> HttpResponse {
+    match result {
+        Ok(client_ids) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message,
+            data: Some(client_ids),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
@@ -0,0 +1,15 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::jwks_service::JwksService;
+
+pub struct AppState {
+    pub jwks_service: Arc<JwksService>,
+}
+
+/// Served as a bare JWK Set (RFC 7517) so EMQX's JWT authenticator can consume it directly.
+pub async fn jwks_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    HttpResponse::Ok().json(data.jwks_service.get_jwks())
+}
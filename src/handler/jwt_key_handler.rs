@@ -0,0 +1,46 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::jwt_key_service::JwtKeyService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::{GetJwtKeyListDTO, RotateJwtKeyDTO};
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub jwt_key_service: Arc<JwtKeyService>,
+}
+
+pub async fn rotate_jwt_key_handler(
+    data: web::Data<AppState>,
+    body: web::Json<RotateJwtKeyDTO>,
+) -> impl Responder {
+    match data.jwt_key_service.rotate_key(body.into_inner()) {
+        Ok(key) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "Signing key rotated successfully",
+            data: Some(key),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn get_jwt_key_list_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match data.jwt_key_service.get_key_list() {
+        Ok(keys) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "Signing key list retrieved successfully",
+            data: Some(GetJwtKeyListDTO { keys }),
+            result: None,
+        }),
+        Err(e) => e.to_http_response(),
+    }
+}
@@ -1,6 +1,17 @@
 pub mod handler_error;
+pub mod auth_request;
 pub mod mqtt_acl_handler;
 pub mod mqtt_login_handler;
 pub mod create_mqtt_handler;
 pub mod get_mqtt_list_handler;
-pub mod soft_delete_mqtt_handler;
\ No newline at end of file
+pub mod soft_delete_mqtt_handler;
+pub mod jwks_handler;
+pub mod refresh_token_handler;
+pub mod token_revocation_handler;
+pub mod jwt_key_handler;
+pub mod token_introspection_handler;
+pub mod acl_rule_handler;
+pub mod acl_group_handler;
+pub mod client_id_handler;
+pub mod peer_network_handler;
+pub mod presence_handler;
\ No newline at end of file
@@ -4,19 +4,31 @@ use std::sync::Arc;
 use crate::services::mqtt_acl_service::MqttAclService;
 use crate::services::service_error::MqttServiceError;
 use crate::dtos::mqtt_dto::MqttAclDTO;
-use crate::dtos::response_dto::ResponseDTO;
+use crate::dtos::response_dto::{AuthResponseFormat, EmqxResponseDTO, ResponseDTO};
+use crate::handler::auth_request::AuthRequest;
 use crate::handler::handler_error::AppError;
+use crate::utils::acl_policy::AclDecision;
 
 pub struct AppState {
     pub mqtt_acl_service: Arc<MqttAclService>,
+    pub response_format: AuthResponseFormat,
 }
 
 pub async fn mqtt_acl_handler(
     data: web::Data<AppState>,
-    body: web::Json<MqttAclDTO>,
+    body: AuthRequest<MqttAclDTO>,
 ) -> impl Responder {
-    match data.mqtt_acl_service.check_acl_permission(body.into_inner()) {
-        Ok(true) => {
+    let decision = body.into_inner().and_then(|dto| data.mqtt_acl_service.check_acl_permission(dto));
+    if data.response_format == AuthResponseFormat::Emqx {
+        let result = match decision {
+            Ok(decision) => decision.as_str(),
+            Err(_) => AclDecision::Deny.as_str(),
+        };
+        return HttpResponse::Ok().json(EmqxResponseDTO::<()> { result, is_superuser: None, data: None });
+    }
+
+    match decision {
+        Ok(AclDecision::Allow) => {
             HttpResponse::Ok().json(ResponseDTO::<()> {
                 success: true,
                 message: "User has access",
@@ -24,7 +36,7 @@ pub async fn mqtt_acl_handler(
                 result: Some("allow"),
             })
         }
-        Ok(false) => {
+        Ok(AclDecision::Deny) => {
             HttpResponse::Ok().json(ResponseDTO::<()> {
                 success: true,
                 message: "User does not have access",
@@ -32,6 +44,14 @@ pub async fn mqtt_acl_handler(
                 result: Some("deny"),
             })
         }
+        Ok(AclDecision::Ignore) => {
+            HttpResponse::Ok().json(ResponseDTO::<()> {
+                success: true,
+                message: "ACL decision deferred to the next authorizer",
+                data: None,
+                result: Some("ignore"),
+            })
+        }
         Err(e) => match &e {
             MqttServiceError::BadRequest(validation_errors) => {
                 e.to_http_response_with_result(Some("deny"), Some(validation_errors))
@@ -39,4 +59,35 @@ pub async fn mqtt_acl_handler(
             _ => e.to_http_response_with_result(Some("deny"), None::<String>),
         },
     }
-}
\ No newline at end of file
+}
+
+pub async fn explain_acl_handler(
+    data: web::Data<AppState>,
+    body: web::Json<MqttAclDTO>,
+) -> impl Responder {
+    match data.mqtt_acl_service.explain_acl_decision(body.into_inner()) {
+        Ok(explanation) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "ACL decision explained successfully",
+            data: Some(explanation),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn get_acl_cache_stats_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    HttpResponse::Ok().json(ResponseDTO {
+        success: true,
+        message: "ACL cache statistics retrieved successfully",
+        data: Some(data.mqtt_acl_service.get_cache_stats()),
+        result: None,
+    })
+}
@@ -4,35 +4,35 @@ use std::sync::Arc;
 use crate::services::mqtt_login_service::MqttLoginService;
 use crate::services::service_error::MqttServiceError;
 use crate::dtos::mqtt_dto::{MqttLoginDTO, MqttJwtDTO};
-use crate::dtos::response_dto::ResponseDTO;
+use crate::dtos::response_dto::{AuthResponseFormat, EmqxResponseDTO, ResponseDTO};
+use crate::handler::auth_request::AuthRequest;
 use crate::handler::handler_error::AppError;
 
 pub struct AppState {
     pub mqtt_login_service: Arc<MqttLoginService>,
+    pub response_format: AuthResponseFormat,
 }
 
 pub async fn login_with_credentials_handler(
     data: web::Data<AppState>,
-    body: web::Json<MqttLoginDTO>,
+    body: AuthRequest<MqttLoginDTO>,
 ) -> impl Responder {
-    match data.mqtt_login_service.login_with_credentials(body.into_inner()) {
-        Ok((_, token)) => {
-            if token.is_empty() {
-                HttpResponse::Ok().json(ResponseDTO::<()> {
-                    success: true,
-                    message: "User MQTT is active",
-                    data: None,
-                    result: Some("allow"),
-                })
-            } else {
-                HttpResponse::Ok().json(ResponseDTO::<MqttJwtDTO> {
-                    success: true,
-                    message: "User MQTT is active",
-                    data: Some(MqttJwtDTO { token }),
-                    result: Some("allow"),
-                })
-            }
-        },
+    let login = body.into_inner().and_then(|dto| data.mqtt_login_service.login_with_credentials(dto));
+    if data.response_format == AuthResponseFormat::Emqx {
+        // EMQX reads anything but a 200 as "ignore", so every rejection is an explicit deny.
+        return HttpResponse::Ok().json(match login {
+            Ok(login) => EmqxResponseDTO { result: "allow", is_superuser: Some(login.is_superuser), data: login.jwt },
+            Err(_) => EmqxResponseDTO { result: "deny", is_superuser: Some(false), data: None },
+        });
+    }
+
+    match login {
+        Ok(login) => HttpResponse::Ok().json(ResponseDTO::<MqttJwtDTO> {
+            success: true,
+            message: "User MQTT is active",
+            data: login.jwt,
+            result: Some("allow"),
+        }),
         Err(e) => match &e {
             MqttServiceError::BadRequest(validation_errors) => {
                 e.to_http_response_with_result(Some("deny"), Some(validation_errors))
@@ -0,0 +1,51 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::peer_network_service::PeerNetworkService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::UsernamePathDTO;
+use crate::dtos::mqtt_dto::MqttNetworksDTO;
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub peer_network_service: Arc<PeerNetworkService>,
+}
+
+pub async fn get_networks_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    networks_response(
+        data.peer_network_service.get_networks(&params.username),
+        "User MQTT networks retrieved successfully",
+    )
+}
+
+pub async fn set_networks_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+    body: web::Json<MqttNetworksDTO>,
+) -> impl Responder {
+    networks_response(
+        data.peer_network_service.set_networks(&params.username, body.into_inner()),
+        "User MQTT networks updated successfully",
+    )
+}
+
+fn networks_response(result: Result<MqttNetworksDTO, MqttServiceError>, message: &'static str) -> HttpResponse {
+    match result {
+        Ok(networks) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message,
+            data: Some(networks),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
@@ -0,0 +1,84 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::presence_service::PresenceService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::UsernamePathDTO;
+use crate::dtos::presence_dto::{GetPresenceListDTO, MqttEventDTO, OfflinePresenceQueryDTO, PresenceDTO};
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub presence_service: Arc<PresenceService>,
+}
+
+pub async fn record_event_handler(
+    data: web::Data<AppState>,
+    body: web::Json<MqttEventDTO>,
+) -> impl Responder {
+    match data.presence_service.record_event(body.into_inner()) {
+        Ok(recorded) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: if recorded { "Event recorded successfully" } else { "Event ignored" },
+            data: None,
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn get_presence_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    match data.presence_service.get_presence(&params.username) {
+        Ok(presence) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "User MQTT presence retrieved successfully",
+            data: Some(presence),
+            result: None,
+        }),
+        Err(e) => e.to_http_response_with_details(None::<String>),
+    }
+}
+
+pub async fn get_presence_list_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    presence_list_response(data.presence_service.get_presence_list())
+}
+
+pub async fn get_online_list_handler(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    presence_list_response(data.presence_service.get_online_list())
+}
+
+pub async fn get_offline_list_handler(
+    data: web::Data<AppState>,
+    query: web::Query<OfflinePresenceQueryDTO>,
+) -> impl Responder {
+    presence_list_response(data.presence_service.get_offline_list(query.hours))
+}
+
+fn presence_list_response(result: Result<Vec<PresenceDTO>, MqttServiceError>) -> HttpResponse {
+    match result {
+        Ok(users) => HttpResponse::Ok().json(ResponseDTO {
+            success: true,
+            message: "User MQTT presence list retrieved successfully",
+            data: Some(GetPresenceListDTO { users }),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
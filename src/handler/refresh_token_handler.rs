@@ -0,0 +1,32 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::refresh_token_service::RefreshTokenService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::{MqttJwtDTO, RefreshTokenDTO};
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub refresh_token_service: Arc<RefreshTokenService>,
+}
+
+pub async fn refresh_token_handler(
+    data: web::Data<AppState>,
+    body: web::Json<RefreshTokenDTO>,
+) -> impl Responder {
+    match data.refresh_token_service.refresh(body.into_inner()) {
+        Ok(jwt) => HttpResponse::Ok().json(ResponseDTO::<MqttJwtDTO> {
+            success: true,
+            message: "Token refreshed successfully",
+            data: Some(jwt),
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
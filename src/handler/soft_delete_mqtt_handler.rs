@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::services::soft_delete_mqtt_service::SoftDeleteMqttService;
 use crate::services::service_error::MqttServiceError;
 use crate::dtos::response_dto::ResponseDTO;
-use crate::dtos::mqtt_dto::DeleteMqttDTO;
+use crate::dtos::mqtt_dto::UsernamePathDTO;
 use crate::handler::handler_error::AppError;
 
 pub struct AppState {
@@ -12,7 +12,7 @@ pub struct AppState {
 
 pub async fn soft_delete_mqtt(
     data: web::Data<AppState>,
-    params: web::Path<DeleteMqttDTO>
+    params: web::Path<UsernamePathDTO>
 ) -> impl Responder {
     let username = &params.username;
     match data.soft_delete_mqtt_service.soft_delete_mqtt(username) {
@@ -0,0 +1,32 @@
+use actix_web::{web, Either, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::token_introspection_service::TokenIntrospectionService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::IntrospectTokenDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub token_introspection_service: Arc<TokenIntrospectionService>,
+}
+
+/// Served as a bare RFC 7662 response so standard OAuth clients and gateways can consume it.
+/// The request is form-encoded as RFC 7662 specifies, or JSON.
+pub async fn introspect_token_handler(
+    data: web::Data<AppState>,
+    body: Either<web::Form<IntrospectTokenDTO>, web::Json<IntrospectTokenDTO>>,
+) -> impl Responder {
+    let dto = match body {
+        Either::Left(form) => form.into_inner(),
+        Either::Right(json) => json.into_inner(),
+    };
+    match data.token_introspection_service.introspect(dto) {
+        Ok(introspection) => HttpResponse::Ok().json(introspection),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
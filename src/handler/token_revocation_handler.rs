@@ -0,0 +1,52 @@
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+use crate::services::token_revocation_service::TokenRevocationService;
+use crate::services::service_error::MqttServiceError;
+use crate::dtos::mqtt_dto::{RevokeTokenDTO, UsernamePathDTO};
+use crate::dtos::response_dto::ResponseDTO;
+use crate::handler::handler_error::AppError;
+
+pub struct AppState {
+    pub token_revocation_service: Arc<TokenRevocationService>,
+}
+
+pub async fn revoke_token_handler(
+    data: web::Data<AppState>,
+    body: web::Json<RevokeTokenDTO>,
+) -> impl Responder {
+    match data.token_revocation_service.revoke_token(body.into_inner()) {
+        Ok(_) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: "Token revoked successfully",
+            data: None,
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
+
+pub async fn revoke_all_tokens_handler(
+    data: web::Data<AppState>,
+    params: web::Path<UsernamePathDTO>,
+) -> impl Responder {
+    match data.token_revocation_service.revoke_all_tokens(&params.username) {
+        Ok(_) => HttpResponse::Ok().json(ResponseDTO::<()> {
+            success: true,
+            message: "All tokens revoked successfully",
+            data: None,
+            result: None,
+        }),
+        Err(e) => match &e {
+            MqttServiceError::BadRequest(validation_errors) => {
+                e.to_http_response_with_details(Some(validation_errors))
+            }
+            _ => e.to_http_response_with_details(None::<String>),
+        },
+    }
+}
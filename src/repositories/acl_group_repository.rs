@@ -0,0 +1,131 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::acl_group_entity::{AclGroupEntity, UserGroupsEntity};
+use crate::repositories::acl_rule_repository::ACL_GROUP_RULES_PREFIX;
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const ACL_GROUP_PREFIX: &str = "acl_group:";
+const USER_GROUPS_PREFIX: &str = "acl_user_groups:";
+
+pub struct AclGroupRepository {
+    db: Arc<DB>,
+}
+
+impl AclGroupRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        AclGroupRepository { db }
+    }
+
+    /// Create a group with an empty rule list, clearing anything left under the same name.
+    pub fn create_group(&self, group: &AclGroupEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | AclGroup] Creating ACL group '{}'.", group.name);
+        let mut batch = WriteBatch::default();
+        batch.put(format!("{}{}", ACL_GROUP_PREFIX, group.name).as_bytes(), Self::encode(group)?);
+        batch.delete(format!("{}{}", ACL_GROUP_RULES_PREFIX, group.name).as_bytes());
+        self.write(batch)
+    }
+
+    pub fn get_group(&self, name: &str) -> Result<Option<AclGroupEntity>, MqttRepositoryError> {
+        self.get(&format!("{}{}", ACL_GROUP_PREFIX, name))
+    }
+
+    pub fn get_group_list(&self) -> Result<Vec<AclGroupEntity>, MqttRepositoryError> {
+        self.scan_prefix(ACL_GROUP_PREFIX)
+            .map(|(_, value)| Ok(decode_from_slice::<AclGroupEntity, _>(&value, standard())?.0))
+            .collect()
+    }
+
+    /// Delete a group, its rules and every membership pointing at it in one batch.
+    pub fn delete_group(&self, name: &str) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | AclGroup] Deleting ACL group '{}'.", name);
+        let mut batch = WriteBatch::default();
+        batch.delete(format!("{}{}", ACL_GROUP_PREFIX, name).as_bytes());
+        batch.delete(format!("{}{}", ACL_GROUP_RULES_PREFIX, name).as_bytes());
+
+        for (key, value) in self.scan_prefix(USER_GROUPS_PREFIX) {
+            let (mut membership, _) = decode_from_slice::<UserGroupsEntity, _>(&value, standard())?;
+            if membership.groups.iter().any(|g| g == name) {
+                membership.groups.retain(|g| g != name);
+                batch.put(key, Self::encode(&membership)?);
+            }
+        }
+        self.write(batch)
+    }
+
+    pub fn get_user_groups(&self, username: &str) -> Result<Vec<String>, MqttRepositoryError> {
+        let membership: Option<UserGroupsEntity> = self.get(&format!("{}{}", USER_GROUPS_PREFIX, username))?;
+        Ok(membership.map(|m| m.groups).unwrap_or_default())
+    }
+
+    pub fn save_user_groups(&self, membership: &UserGroupsEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | AclGroup] Saving {} groups for user MQTT '{}'.", membership.groups.len(), membership.username);
+        let key = format!("{}{}", USER_GROUPS_PREFIX, membership.username);
+        let mut batch = WriteBatch::default();
+        if membership.groups.is_empty() {
+            batch.delete(key.as_bytes());
+        } else {
+            batch.put(key.as_bytes(), Self::encode(membership)?);
+        }
+        self.write(batch)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> impl Iterator<Item = (Box<[u8]>, Box<[u8]>)> + '_ {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(false);
+
+        let prefix = prefix.as_bytes().to_vec();
+        self.db
+            .iterator_opt(IteratorMode::From(&prefix, Direction::Forward), read_opts)
+            .flatten()
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+    }
+
+    fn encode<T: bincode::Encode>(value: &T) -> Result<Vec<u8>, MqttRepositoryError> {
+        encode_to_vec(value, standard()).map_err(|e| {
+            error!("[Repository | AclGroup] Failed to encode data: {e}");
+            MqttRepositoryError::Encode(e)
+        })
+    }
+
+    fn get<T: bincode::Decode<()>>(&self, key: &str) -> Result<Option<T>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let value = match self.db.get_opt(key.as_bytes(), &read_opts) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[Repository | AclGroup] Database read error for key {key}: {e}");
+                return Err(MqttRepositoryError::Database(e));
+            }
+        };
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        match decode_from_slice::<T, _>(&value, standard()) {
+            Ok((decoded, _)) => Ok(Some(decoded)),
+            Err(e) => {
+                error!("[Repository | AclGroup] Failed to decode data for key {key}: {e}");
+                Err(MqttRepositoryError::Decode(e))
+            }
+        }
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), MqttRepositoryError> {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        match self.db.write_opt(batch, &write_opts) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("[Repository | AclGroup] Database write error: {e}");
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::acl_rule_entity::AclRuleEntity;
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const ACL_RULES_PREFIX: &str = "acl_rules:";
+pub const ACL_GROUP_RULES_PREFIX: &str = "acl_group_rules:";
+
+pub struct AclRuleRepository {
+    db: Arc<DB>,
+}
+
+impl AclRuleRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        AclRuleRepository { db }
+    }
+
+    /// The user's rules in evaluation order; empty when none were configured.
+    pub fn get_rules(&self, username: &str) -> Result<Vec<AclRuleEntity>, MqttRepositoryError> {
+        self.get(&format!("{}{}", ACL_RULES_PREFIX, username))
+    }
+
+    /// Replace the user's whole rule list, so a reorder is written atomically.
+    pub fn save_rules(&self, username: &str, rules: &[AclRuleEntity]) -> Result<(), MqttRepositoryError> {
+        self.save(&format!("{}{}", ACL_RULES_PREFIX, username), rules)
+    }
+
+    pub fn get_group_rules(&self, group: &str) -> Result<Vec<AclRuleEntity>, MqttRepositoryError> {
+        self.get(&format!("{}{}", ACL_GROUP_RULES_PREFIX, group))
+    }
+
+    pub fn save_group_rules(&self, group: &str, rules: &[AclRuleEntity]) -> Result<(), MqttRepositoryError> {
+        self.save(&format!("{}{}", ACL_GROUP_RULES_PREFIX, group), rules)
+    }
+
+    /// Every user's rule list, keyed by username.
+    pub fn get_all_rules(&self) -> Result<Vec<(String, Vec<AclRuleEntity>)>, MqttRepositoryError> {
+        self.scan(ACL_RULES_PREFIX)
+    }
+
+    /// Every group's rule list, keyed by group name.
+    pub fn get_all_group_rules(&self) -> Result<Vec<(String, Vec<AclRuleEntity>)>, MqttRepositoryError> {
+        self.scan(ACL_GROUP_RULES_PREFIX)
+    }
+
+    fn scan(&self, prefix: &str) -> Result<Vec<(String, Vec<AclRuleEntity>)>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(false);
+
+        self.db
+            .iterator_opt(IteratorMode::From(prefix.as_bytes(), Direction::Forward), read_opts)
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix.as_bytes()))
+            .map(|(key, value)| {
+                let owner = String::from_utf8_lossy(&key[prefix.len()..]).into_owned();
+                let (rules, _) = decode_from_slice::<Vec<AclRuleEntity>, _>(&value, standard())?;
+                Ok((owner, rules))
+            })
+            .collect()
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<AclRuleEntity>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let value = match self.db.get_opt(key.as_bytes(), &read_opts) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[Repository | AclRule] Database read error for key {key}: {e}");
+                return Err(MqttRepositoryError::Database(e));
+            }
+        };
+
+        let Some(value) = value else {
+            debug!("[Repository | AclRule] No ACL rules stored under '{}'.", key);
+            return Ok(Vec::new());
+        };
+
+        match decode_from_slice::<Vec<AclRuleEntity>, _>(&value, standard()) {
+            Ok((rules, _)) => Ok(rules),
+            Err(e) => {
+                error!("[Repository | AclRule] Failed to decode ACL rules for key {key}: {e}");
+                Err(MqttRepositoryError::Decode(e))
+            }
+        }
+    }
+
+    fn save(&self, key: &str, rules: &[AclRuleEntity]) -> Result<(), MqttRepositoryError> {
+        let mut batch = WriteBatch::default();
+        if rules.is_empty() {
+            batch.delete(key.as_bytes());
+        } else {
+            let encoded = match encode_to_vec(rules, standard()) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("[Repository | AclRule] Failed to encode ACL rules for key {key}: {e}");
+                    return Err(MqttRepositoryError::Encode(e));
+                }
+            };
+            batch.put(key.as_bytes(), encoded);
+        }
+
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        match self.db.write_opt(batch, &write_opts) {
+            Ok(_) => {
+                debug!("[Repository | AclRule] Saved {} ACL rules under '{}'.", rules.len(), key);
+                Ok(())
+            }
+            Err(e) => {
+                error!("[Repository | AclRule] Database write error for key {key}: {e}");
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
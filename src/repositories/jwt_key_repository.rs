@@ -0,0 +1,73 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::jwt_key_entity::JwtKeyEntity;
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const JWT_KEY_PREFIX: &str = "jwt_key:";
+
+pub struct JwtKeyRepository {
+    db: Arc<DB>,
+}
+
+impl JwtKeyRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        JwtKeyRepository { db }
+    }
+
+    /// Write several keys in one batch so a rotation never leaves two active keys behind.
+    pub fn save_keys(&self, keys: &[&JwtKeyEntity]) -> Result<(), MqttRepositoryError> {
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            debug!("[Repository | JwtKey] Saving signing key '{}' (active={}).", key.kid, key.is_active);
+            let encoded = match encode_to_vec(*key, standard()) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("[Repository | JwtKey] Failed to encode signing key {}: {e}", key.kid);
+                    return Err(MqttRepositoryError::Encode(e));
+                }
+            };
+            batch.put(format!("{}{}", JWT_KEY_PREFIX, key.kid).as_bytes(), encoded);
+        }
+        self.write(batch)
+    }
+
+    pub fn get_key_list(&self) -> Result<Vec<JwtKeyEntity>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let prefix = JWT_KEY_PREFIX.as_bytes();
+        let mut keys = Vec::new();
+        for (key, value) in self.db.iterator_opt(IteratorMode::From(prefix, Direction::Forward), read_opts).flatten() {
+            if !key.starts_with(prefix) {
+                break;
+            }
+
+            match decode_from_slice::<JwtKeyEntity, _>(&value, standard()) {
+                Ok((entity, _)) => keys.push(entity),
+                Err(e) => {
+                    error!("[Repository | JwtKey] Failed to decode signing key {}: {e}", String::from_utf8_lossy(&key));
+                    return Err(MqttRepositoryError::Decode(e));
+                }
+            }
+        }
+
+        debug!("[Repository | JwtKey] Loaded {} signing keys.", keys.len());
+        Ok(keys)
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), MqttRepositoryError> {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        match self.db.write_opt(batch, &write_opts) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("[Repository | JwtKey] Database write error: {e}");
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
@@ -2,4 +2,11 @@ pub mod repository_error;
 pub mod create_mqtt_repository;
 pub mod get_mqtt_list_repository;
 pub mod get_mqtt_by_username_repository;
-pub mod soft_delete_mqtt_repository;
\ No newline at end of file
+pub mod soft_delete_mqtt_repository;
+pub mod update_mqtt_repository;
+pub mod refresh_token_repository;
+pub mod token_revocation_repository;
+pub mod jwt_key_repository;
+pub mod acl_rule_repository;
+pub mod acl_group_repository;
+pub mod presence_repository;
\ No newline at end of file
@@ -0,0 +1,83 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::presence_entity::PresenceEntity;
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const PRESENCE_PREFIX: &str = "presence:";
+
+pub struct PresenceRepository {
+    db: Arc<DB>,
+}
+
+impl PresenceRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        PresenceRepository { db }
+    }
+
+    pub fn get_presence(&self, username: &str) -> Result<Option<PresenceEntity>, MqttRepositoryError> {
+        let key = format!("{}{}", PRESENCE_PREFIX, username);
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let value = match self.db.get_opt(key.as_bytes(), &read_opts) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[Repository | Presence] Database read error for user MQTT {username}: {e}");
+                return Err(MqttRepositoryError::Database(e));
+            }
+        };
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        match decode_from_slice::<PresenceEntity, _>(&value, standard()) {
+            Ok((presence, _)) => Ok(Some(presence)),
+            Err(e) => {
+                error!("[Repository | Presence] Failed to decode presence of user MQTT {username}: {e}");
+                Err(MqttRepositoryError::Decode(e))
+            }
+        }
+    }
+
+    pub fn get_presence_list(&self) -> Result<Vec<PresenceEntity>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(false);
+
+        let prefix = PRESENCE_PREFIX.as_bytes();
+        let mut presences = Vec::new();
+        for item in self.db.iterator_opt(IteratorMode::From(prefix, Direction::Forward), read_opts) {
+            let (key, value) = item.map_err(|e| {
+                error!("[Repository | Presence] Database iteration error: {e}");
+                MqttRepositoryError::Database(e)
+            })?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            presences.push(decode_from_slice::<PresenceEntity, _>(&value, standard())?.0);
+        }
+        debug!("[Repository | Presence] Retrieved {} presence records.", presences.len());
+        Ok(presences)
+    }
+
+    pub fn save_presence(&self, presence: &PresenceEntity) -> Result<(), MqttRepositoryError> {
+        let key = format!("{}{}", PRESENCE_PREFIX, presence.username);
+        let encoded = encode_to_vec(presence, standard()).map_err(|e| {
+            error!("[Repository | Presence] Failed to encode presence of user MQTT {}: {e}", presence.username);
+            MqttRepositoryError::Encode(e)
+        })?;
+
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        debug!("[Repository | Presence] Saving presence of user MQTT '{}'.", presence.username);
+        self.db.put_opt(key.as_bytes(), &encoded, &write_opts).map_err(|e| {
+            error!("[Repository | Presence] Database write error for user MQTT {}: {e}", presence.username);
+            MqttRepositoryError::Database(e)
+        })
+    }
+}
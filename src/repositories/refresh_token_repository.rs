@@ -0,0 +1,166 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+use std::collections::HashSet;
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::refresh_token_entity::{RefreshFamilyEntity, RefreshTokenEntity};
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const TOKEN_PREFIX: &str = "refresh_token:";
+const FAMILY_PREFIX: &str = "refresh_family:";
+
+pub struct RefreshTokenRepository {
+    db: Arc<DB>,
+}
+
+impl RefreshTokenRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        RefreshTokenRepository { db }
+    }
+
+    /// Store the first token of a new family together with the family record.
+    pub fn create_family(&self, family: &RefreshFamilyEntity, token: &RefreshTokenEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | RefreshToken] Creating token family '{}' for user MQTT '{}'.", family.family_id, family.username);
+        let mut batch = WriteBatch::default();
+        batch.put(Self::family_key(&family.family_id), Self::encode(family)?);
+        batch.put(Self::token_key(&token.token_hash), Self::encode(token)?);
+        self.write(batch)
+    }
+
+    /// Mark `used` as consumed and store its successor in one atomic write.
+    pub fn rotate(&self, used: &RefreshTokenEntity, next: &RefreshTokenEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | RefreshToken] Rotating refresh token in family '{}'.", used.family_id);
+        let mut batch = WriteBatch::default();
+        batch.put(Self::token_key(&used.token_hash), Self::encode(used)?);
+        batch.put(Self::token_key(&next.token_hash), Self::encode(next)?);
+        self.write(batch)
+    }
+
+    pub fn save_family(&self, family: &RefreshFamilyEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | RefreshToken] Saving token family '{}'.", family.family_id);
+        let mut batch = WriteBatch::default();
+        batch.put(Self::family_key(&family.family_id), Self::encode(family)?);
+        self.write(batch)
+    }
+
+    pub fn get_token(&self, token_hash: &str) -> Result<Option<RefreshTokenEntity>, MqttRepositoryError> {
+        self.get(&Self::token_key(token_hash))
+    }
+
+    pub fn get_family(&self, family_id: &str) -> Result<Option<RefreshFamilyEntity>, MqttRepositoryError> {
+        self.get(&Self::family_key(family_id))
+    }
+
+    /// Delete tokens that expired before `now`, families that are revoked or have no unexpired
+    /// token left, and the tokens of revoked families. Returns the number of entries removed.
+    pub fn prune_expired(&self, now: i64) -> Result<usize, MqttRepositoryError> {
+        // Families are scanned before tokens: a family created in between is not seen here, so it
+        // cannot be mistaken for one without tokens.
+        let mut families = Vec::new();
+        for (key, value) in self.scan_prefix(FAMILY_PREFIX) {
+            let (family, _) = decode_from_slice::<RefreshFamilyEntity, _>(&value, standard())?;
+            families.push((key, family));
+        }
+
+        let mut batch = WriteBatch::default();
+        let mut live_tokens = Vec::new();
+        for (key, value) in self.scan_prefix(TOKEN_PREFIX) {
+            let (token, _) = decode_from_slice::<RefreshTokenEntity, _>(&value, standard())?;
+            if token.expires_at < now {
+                batch.delete(key);
+            } else {
+                live_tokens.push((key, token.family_id));
+            }
+        }
+
+        let live_families: HashSet<&str> = live_tokens.iter().map(|(_, family_id)| family_id.as_str()).collect();
+        let mut revoked_families = HashSet::new();
+        for (key, family) in &families {
+            if family.is_revoked {
+                revoked_families.insert(family.family_id.as_str());
+                batch.delete(key);
+            } else if !live_families.contains(family.family_id.as_str()) {
+                batch.delete(key);
+            }
+        }
+        for (key, family_id) in &live_tokens {
+            if revoked_families.contains(family_id.as_str()) {
+                batch.delete(key);
+            }
+        }
+
+        let removed = batch.len();
+        if removed > 0 {
+            self.write(batch)?;
+        }
+        debug!("[Repository | RefreshToken] Pruned {} expired refresh token entries.", removed);
+        Ok(removed)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> impl Iterator<Item = (Box<[u8]>, Box<[u8]>)> + '_ {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(false);
+
+        let prefix = prefix.as_bytes().to_vec();
+        self.db
+            .iterator_opt(IteratorMode::From(&prefix, Direction::Forward), read_opts)
+            .flatten()
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+    }
+
+    fn token_key(token_hash: &str) -> String {
+        format!("{}{}", TOKEN_PREFIX, token_hash)
+    }
+
+    fn family_key(family_id: &str) -> String {
+        format!("{}{}", FAMILY_PREFIX, family_id)
+    }
+
+    fn encode<T: bincode::Encode>(value: &T) -> Result<Vec<u8>, MqttRepositoryError> {
+        encode_to_vec(value, standard()).map_err(|e| {
+            error!("[Repository | RefreshToken] Failed to encode refresh token data: {e}");
+            MqttRepositoryError::Encode(e)
+        })
+    }
+
+    fn get<T: bincode::Decode<()>>(&self, key: &str) -> Result<Option<T>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let value = match self.db.get_opt(key.as_bytes(), &read_opts) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[Repository | RefreshToken] Database read error for key {key}: {e}");
+                return Err(MqttRepositoryError::Database(e));
+            }
+        };
+
+        let Some(value) = value else {
+            debug!("[Repository | RefreshToken] Key '{}' not found in database.", key);
+            return Ok(None);
+        };
+
+        match decode_from_slice::<T, _>(&value, standard()) {
+            Ok((decoded, _)) => Ok(Some(decoded)),
+            Err(e) => {
+                error!("[Repository | RefreshToken] Failed to decode data for key {key}: {e}");
+                Err(MqttRepositoryError::Decode(e))
+            }
+        }
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), MqttRepositoryError> {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        match self.db.write_opt(batch, &write_opts) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("[Repository | RefreshToken] Database write error: {e}");
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use rocksdb::{DB, Direction, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, decode_from_slice, config::standard};
+use log::{debug, error};
+use crate::entities::revoked_token_entity::{RevokedTokenEntity, UserTokenRevocationEntity};
+use crate::repositories::repository_error::MqttRepositoryError;
+
+const REVOKED_JTI_PREFIX: &str = "revoked_jti:";
+const USER_REVOCATION_PREFIX: &str = "revoked_user:";
+
+pub struct TokenRevocationRepository {
+    db: Arc<DB>,
+}
+
+impl TokenRevocationRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        TokenRevocationRepository { db }
+    }
+
+    pub fn revoke_jti(&self, revoked: &RevokedTokenEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | TokenRevocation] Revoking token jti '{}' of user MQTT '{}'.", revoked.jti, revoked.username);
+        self.put(&format!("{}{}", REVOKED_JTI_PREFIX, revoked.jti), revoked)
+    }
+
+    pub fn is_jti_revoked(&self, jti: &str) -> Result<bool, MqttRepositoryError> {
+        let key = format!("{}{}", REVOKED_JTI_PREFIX, jti);
+        Ok(self.get::<RevokedTokenEntity>(&key)?.is_some())
+    }
+
+    pub fn revoke_user(&self, revocation: &UserTokenRevocationEntity) -> Result<(), MqttRepositoryError> {
+        debug!("[Repository | TokenRevocation] Revoking all tokens of user MQTT '{}' issued before {} ms.", revocation.username, revocation.revoked_before_ms);
+        self.put(&format!("{}{}", USER_REVOCATION_PREFIX, revocation.username), revocation)
+    }
+
+    pub fn get_user_revocation(&self, username: &str) -> Result<Option<UserTokenRevocationEntity>, MqttRepositoryError> {
+        self.get(&format!("{}{}", USER_REVOCATION_PREFIX, username))
+    }
+
+    /// Delete revoked jtis whose token expired before `now`, and user-wide revocations
+    /// older than `user_revocation_horizon_ms`. Returns the number of entries removed.
+    pub fn prune_expired(&self, now: i64, user_revocation_horizon_ms: i64) -> Result<usize, MqttRepositoryError> {
+        let mut batch = WriteBatch::default();
+
+        for (key, value) in self.scan_prefix(REVOKED_JTI_PREFIX) {
+            let (revoked, _) = decode_from_slice::<RevokedTokenEntity, _>(&value, standard())?;
+            if revoked.expires_at < now {
+                batch.delete(key);
+            }
+        }
+
+        for (key, value) in self.scan_prefix(USER_REVOCATION_PREFIX) {
+            let (revocation, _) = decode_from_slice::<UserTokenRevocationEntity, _>(&value, standard())?;
+            if revocation.revoked_before_ms < user_revocation_horizon_ms {
+                batch.delete(key);
+            }
+        }
+
+        let removed = batch.len();
+        if removed > 0 {
+            self.write(batch)?;
+        }
+        debug!("[Repository | TokenRevocation] Pruned {} expired revocation entries.", removed);
+        Ok(removed)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> impl Iterator<Item = (Box<[u8]>, Box<[u8]>)> + '_ {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(false);
+
+        let prefix = prefix.as_bytes().to_vec();
+        self.db
+            .iterator_opt(IteratorMode::From(&prefix, Direction::Forward), read_opts)
+            .flatten()
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+    }
+
+    fn put<T: bincode::Encode>(&self, key: &str, value: &T) -> Result<(), MqttRepositoryError> {
+        let encoded = match encode_to_vec(value, standard()) {
+            Ok(data) => data,
+            Err(e) => {
+                error!("[Repository | TokenRevocation] Failed to encode data for key {key}: {e}");
+                return Err(MqttRepositoryError::Encode(e));
+            }
+        };
+
+        let mut batch = WriteBatch::default();
+        batch.put(key.as_bytes(), encoded);
+        self.write(batch)
+    }
+
+    fn get<T: bincode::Decode<()>>(&self, key: &str) -> Result<Option<T>, MqttRepositoryError> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_verify_checksums(false);
+        read_opts.fill_cache(true);
+
+        let value = match self.db.get_opt(key.as_bytes(), &read_opts) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[Repository | TokenRevocation] Database read error for key {key}: {e}");
+                return Err(MqttRepositoryError::Database(e));
+            }
+        };
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        match decode_from_slice::<T, _>(&value, standard()) {
+            Ok((decoded, _)) => Ok(Some(decoded)),
+            Err(e) => {
+                error!("[Repository | TokenRevocation] Failed to decode data for key {key}: {e}");
+                Err(MqttRepositoryError::Decode(e))
+            }
+        }
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), MqttRepositoryError> {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        match self.db.write_opt(batch, &write_opts) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("[Repository | TokenRevocation] Database write error: {e}");
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
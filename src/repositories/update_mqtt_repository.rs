@@ -0,0 +1,49 @@
+use rocksdb::{DB, WriteOptions};
+use std::sync::Arc;
+use bincode::{encode_to_vec, config::standard};
+use log::{debug, error};
+use crate::entities::mqtt_entity::MqttEntity;
+use crate::repositories::repository_error::MqttRepositoryError;
+
+pub struct UpdateMqttRepository {
+    db: Arc<DB>,
+}
+
+impl UpdateMqttRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        UpdateMqttRepository { db }
+    }
+
+    pub fn update_mqtt(&self, mqtt: &MqttEntity) -> Result<(), MqttRepositoryError> {
+        // Build RocksDB key
+        let key: String = format!("mqtt:{}", mqtt.username);
+
+        // Encode the updated entity
+        debug!("[Repository | UpdateMQTT] Encoding updated user MQTT data for '{}'.", mqtt.username);
+        let encoded = match encode_to_vec(mqtt, standard()) {
+            Ok(data) => data,
+            Err(e) => {
+                error!("[Repository | UpdateMQTT] Failed to encode user MQTT data for {}: {e}", mqtt.username);
+                debug!("[Repository | UpdateMQTT] Encode error for user MQTT '{}': {:#?}", mqtt.username, e);
+                return Err(MqttRepositoryError::Encode(e));
+            }
+        };
+
+        // Write updated entity to DB
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(false);
+
+        debug!("[Repository | UpdateMQTT] Writing updated user MQTT '{}' to database.", mqtt.username);
+        match self.db.put_opt(key.as_bytes(), &encoded, &write_opts) {
+            Ok(_) => {
+                debug!("[Repository | UpdateMQTT] Successfully updated user MQTT '{}'.", mqtt.username);
+                Ok(())
+            }
+            Err(e) => {
+                error!("[Repository | UpdateMQTT] Database write error for user MQTT {}: {e}", mqtt.username);
+                debug!("[Repository | UpdateMQTT] Database write error for user MQTT '{}': {:#?}", mqtt.username, e);
+                Err(MqttRepositoryError::Database(e))
+            }
+        }
+    }
+}
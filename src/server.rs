@@ -1,8 +1,10 @@
 use actix_web::{App, HttpServer, HttpResponse, middleware, web, Responder};
-use chrono::Local;
+use chrono::{Local, Utc};
 use std::sync::Arc;
+use std::time::Duration;
 use std::io::Write;
 use log::{info, error};
+use jsonwebtoken::Algorithm;
 
 use crate::infrastructure::rocksdb::{init_rocksdb, close_rocksdb};
 use crate::middleware::api_key::ApiKeyMiddleware;
@@ -11,20 +13,69 @@ use crate::middleware::logger_request::RequestLoggerMiddleware;
 
 use crate::handler::create_mqtt_handler::{create_mqtt_handler, AppState as CreateMqttAppState};
 use crate::handler::get_mqtt_list_handler::{get_mqtt_list_handler, AppState as GetListAppState};
+use crate::handler::auth_request::FieldMapping;
 use crate::handler::mqtt_login_handler::{login_with_credentials_handler, AppState as MqttLoginAppState};
-use crate::handler::mqtt_acl_handler::{mqtt_acl_handler, AppState as MqttAclAppState};
+use crate::handler::mqtt_acl_handler::{mqtt_acl_handler, explain_acl_handler, get_acl_cache_stats_handler, AppState as MqttAclAppState};
 use crate::handler::soft_delete_mqtt_handler::{soft_delete_mqtt, AppState as SoftDeleteMqttAppState};
+use crate::handler::jwks_handler::{jwks_handler, AppState as JwksAppState};
+use crate::handler::refresh_token_handler::{refresh_token_handler, AppState as RefreshTokenAppState};
+use crate::handler::token_revocation_handler::{revoke_token_handler, revoke_all_tokens_handler, AppState as TokenRevocationAppState};
+use crate::handler::jwt_key_handler::{rotate_jwt_key_handler, get_jwt_key_list_handler, AppState as JwtKeyAppState};
+use crate::handler::token_introspection_handler::{introspect_token_handler, AppState as TokenIntrospectionAppState};
+use crate::handler::acl_rule_handler::{
+    get_acl_rule_list_handler, create_acl_rule_handler, update_acl_rule_handler, delete_acl_rule_handler,
+    get_acl_group_rule_list_handler, create_acl_group_rule_handler, update_acl_group_rule_handler, delete_acl_group_rule_handler,
+    AppState as AclRuleAppState,
+};
+use crate::handler::acl_group_handler::{
+    create_acl_group_handler, get_acl_group_list_handler, delete_acl_group_handler,
+    get_user_groups_handler, set_user_groups_handler, add_user_group_handler, remove_user_group_handler,
+    AppState as AclGroupAppState,
+};
+use crate::handler::client_id_handler::{get_client_ids_handler, set_client_ids_handler, AppState as ClientIdAppState};
+use crate::handler::peer_network_handler::{get_networks_handler, set_networks_handler, AppState as PeerNetworkAppState};
+use crate::handler::presence_handler::{
+    record_event_handler, get_presence_handler, get_presence_list_handler, get_online_list_handler, get_offline_list_handler,
+    AppState as PresenceAppState,
+};
 
 use crate::services::create_mqtt_service::CreateMqttService;
 use crate::services::get_mqtt_list_service::GetMqttListService;
 use crate::services::mqtt_login_service::MqttLoginService;
 use crate::services::mqtt_acl_service::MqttAclService;
 use crate::services::soft_delete_mqtt_service::SoftDeleteMqttService;
+use crate::services::jwks_service::JwksService;
+use crate::services::refresh_token_service::RefreshTokenService;
+use crate::services::token_revocation_service::TokenRevocationService;
+use crate::services::jwt_key_service::JwtKeyService;
+use crate::services::token_introspection_service::TokenIntrospectionService;
+use crate::services::acl_rule_service::AclRuleService;
+use crate::services::acl_group_service::AclGroupService;
+use crate::services::client_id_service::ClientIdService;
+use crate::services::peer_network_service::PeerNetworkService;
+use crate::services::presence_service::PresenceService;
 
 use crate::repositories::create_mqtt_repository::CreateMqttRepository;
 use crate::repositories::get_mqtt_list_repository::GetMqttListRepository;
 use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
 use crate::repositories::soft_delete_mqtt_repository::SoftDeleteMqttRepository;
+use crate::repositories::update_mqtt_repository::UpdateMqttRepository;
+use crate::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::repositories::token_revocation_repository::TokenRevocationRepository;
+use crate::repositories::jwt_key_repository::JwtKeyRepository;
+use crate::repositories::acl_rule_repository::AclRuleRepository;
+use crate::repositories::acl_group_repository::AclGroupRepository;
+use crate::repositories::presence_repository::PresenceRepository;
+
+use crate::entities::jwt_key_entity::JwtKeyEntity;
+
+use crate::dtos::response_dto::AuthResponseFormat;
+
+use crate::utils::jwt_keys::{default_kid, parse_algorithm};
+use crate::utils::jwt_sign::JwtSettings;
+use crate::utils::acl_cache::AclDecisionCache;
+use crate::utils::user_lock::UserLocks;
+use crate::utils::acl_policy::{parse_protected_topics, parse_share_groups, validate_namespace_template, AclDecision, AclSettings};
 
 async fn healthcheck() -> impl Responder {
     HttpResponse::Ok()
@@ -40,8 +91,65 @@ pub async fn run_server() -> std::io::Result<()> {
     let env = env_logger::Env::new().filter_or("LOG_LEVEL", "info");
     let db_path = std::env::var("DB_PATH")
         .expect("❌ Environment variable DB_PATH is not set");
-    let secret_key = std::env::var("SECRET_KEY")
-        .expect("❌ Environment variable SECRET_KEY is not set");
+    let jwt_algorithm = std::env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string());
+    let jwt_algorithm = parse_algorithm(&jwt_algorithm)
+        .expect("❌ Environment variable JWT_ALGORITHM must be one of HS256, RS256, ES256, EdDSA");
+    let jwt_key_id = std::env::var("JWT_KEY_ID").ok();
+    let jwt_defaults = JwtSettings::default();
+    let jwt_settings = Arc::new(JwtSettings {
+        issuer: std::env::var("JWT_ISSUER").ok(),
+        audience: std::env::var("JWT_AUDIENCE").ok(),
+        subject: std::env::var("JWT_SUBJECT")
+            .map(|v| JwtSettings::validate_subject(&v).map(|()| v)
+                .expect("❌ Environment variable JWT_SUBJECT may only use the ${username} placeholder"))
+            .unwrap_or(jwt_defaults.subject),
+        default_ttl_secs: std::env::var("JWT_TTL_SECONDS")
+            .map(|v| v.parse().expect("❌ Environment variable JWT_TTL_SECONDS must be a number of seconds"))
+            .unwrap_or(jwt_defaults.default_ttl_secs),
+        max_ttl_secs: std::env::var("JWT_MAX_TTL_SECONDS")
+            .map(|v| v.parse().expect("❌ Environment variable JWT_MAX_TTL_SECONDS must be a number of seconds"))
+            .unwrap_or(jwt_defaults.max_ttl_secs),
+        refresh_ttl_secs: std::env::var("JWT_REFRESH_TTL_SECONDS")
+            .map(|v| v.parse().expect("❌ Environment variable JWT_REFRESH_TTL_SECONDS must be a number of seconds"))
+            .unwrap_or(jwt_defaults.refresh_ttl_secs),
+        embed_acl: std::env::var("JWT_EMBED_ACL")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(jwt_defaults.embed_acl),
+    });
+    let acl_defaults = AclSettings::default();
+    let acl_settings = Arc::new(AclSettings {
+        no_match: std::env::var("ACL_NO_MATCH")
+            .map(|v| AclDecision::parse(&v).expect("❌ Environment variable ACL_NO_MATCH must be one of allow, deny, ignore"))
+            .unwrap_or(acl_defaults.no_match),
+        unknown_user: std::env::var("ACL_UNKNOWN_USER")
+            .map(|v| AclDecision::parse(&v)
+                .filter(|d| *d != AclDecision::Allow)
+                .expect("❌ Environment variable ACL_UNKNOWN_USER must be one of deny, ignore"))
+            .unwrap_or(acl_defaults.unknown_user),
+        namespace: std::env::var("ACL_NAMESPACE")
+            .map(|v| validate_namespace_template(&v).map(|()| v)
+                .expect("❌ Environment variable ACL_NAMESPACE must be a topic filter template"))
+            .unwrap_or(acl_defaults.namespace),
+        protected_topics: std::env::var("ACL_PROTECTED_TOPICS")
+            .map(|v| parse_protected_topics(&v)
+                .expect("❌ Environment variable ACL_PROTECTED_TOPICS must be a comma-separated list of topic filters"))
+            .unwrap_or(acl_defaults.protected_topics),
+        share_groups_allow: std::env::var("ACL_SHARE_GROUPS_ALLOW")
+            .map(|v| parse_share_groups(&v))
+            .unwrap_or(acl_defaults.share_groups_allow),
+        share_groups_deny: std::env::var("ACL_SHARE_GROUPS_DENY")
+            .map(|v| parse_share_groups(&v))
+            .unwrap_or(acl_defaults.share_groups_deny),
+        timezone: std::env::var("ACL_TIMEZONE")
+            .map(|v| v.parse().expect("❌ Environment variable ACL_TIMEZONE must be an IANA timezone such as Europe/Berlin"))
+            .unwrap_or(acl_defaults.timezone),
+    });
+    let response_format = std::env::var("AUTH_RESPONSE_FORMAT")
+        .map(|v| AuthResponseFormat::parse(&v).expect("❌ Environment variable AUTH_RESPONSE_FORMAT must be one of envelope, emqx"))
+        .unwrap_or(AuthResponseFormat::Envelope);
+    let field_mapping = web::Data::new(std::env::var("AUTH_FIELD_MAP")
+        .map(|v| FieldMapping::parse(&v).expect("❌ Environment variable AUTH_FIELD_MAP must be a comma-separated list of incoming=field pairs"))
+        .unwrap_or_default());
 
     // =====================
     // 🪵 Initialize logger with custom format + color
@@ -79,10 +187,33 @@ pub async fn run_server() -> std::io::Result<()> {
     let db = init_rocksdb(&db_path)
         .map_err(|e| {
             error!("❌ Failed to initialize RocksDB at {}: {}", db_path, e);
-            std::io::Error::new(std::io::ErrorKind::Other, "Failed to initialize RocksDB")
+            std::io::Error::other("Failed to initialize RocksDB")
         })?;
     info!("🟢 RocksDB initialized successfully at {}", db_path);
 
+    // =====================
+    // 🔑 JWT Signing Key
+    // =====================
+    let configured_key = if jwt_algorithm == Algorithm::HS256 {
+        let secret_key = std::env::var("SECRET_KEY")
+            .expect("❌ Environment variable SECRET_KEY is not set");
+        let kid = jwt_key_id.unwrap_or_else(|| default_kid(secret_key.as_bytes()));
+        JwtKeyEntity::create(kid, "HS256", Some(secret_key), None, None, Utc::now().timestamp())
+    } else {
+        let private_key_path = std::env::var("JWT_PRIVATE_KEY_PATH")
+            .expect("❌ Environment variable JWT_PRIVATE_KEY_PATH is not set");
+        let public_key_path = std::env::var("JWT_PUBLIC_KEY_PATH")
+            .expect("❌ Environment variable JWT_PUBLIC_KEY_PATH is not set");
+        let private_pem = std::fs::read_to_string(&private_key_path).inspect_err(|e| {
+            error!("❌ Failed to read JWT private key at {}: {}", private_key_path, e);
+        })?;
+        let public_pem = std::fs::read_to_string(&public_key_path).inspect_err(|e| {
+            error!("❌ Failed to read JWT public key at {}: {}", public_key_path, e);
+        })?;
+        let kid = jwt_key_id.unwrap_or_else(|| default_kid(public_pem.as_bytes()));
+        JwtKeyEntity::create(kid, format!("{:?}", jwt_algorithm), None, Some(private_pem), Some(public_pem), Utc::now().timestamp())
+    };
+
     // =====================
     // 🧩 Repository Layer
     // =====================
@@ -90,24 +221,101 @@ pub async fn run_server() -> std::io::Result<()> {
     let get_mqtt_list_repo = Arc::new(GetMqttListRepository::new(Arc::clone(&db)));
     let get_by_username_repo = Arc::new(GetMqttByUsernameRepository::new(Arc::clone(&db)));
     let soft_delete_mqtt_repo = Arc::new(SoftDeleteMqttRepository::new(Arc::clone(&db)));
+    let update_mqtt_repo = Arc::new(UpdateMqttRepository::new(Arc::clone(&db)));
+    let refresh_token_repo = Arc::new(RefreshTokenRepository::new(Arc::clone(&db)));
+    let token_revocation_repo = Arc::new(TokenRevocationRepository::new(Arc::clone(&db)));
+    let jwt_key_repo = Arc::new(JwtKeyRepository::new(Arc::clone(&db)));
+    let acl_rule_repo = Arc::new(AclRuleRepository::new(Arc::clone(&db)));
+    let acl_group_repo = Arc::new(AclGroupRepository::new(Arc::clone(&db)));
+    let presence_repo = Arc::new(PresenceRepository::new(Arc::clone(&db)));
 
     // =====================
     // 🛠️ Service Layer
     // =====================
-    let create_mqtt_service = Arc::new(CreateMqttService::new(Arc::clone(&create_mqtt_repo), Arc::clone(&get_by_username_repo)));
+    let jwt_key_service = Arc::new(JwtKeyService::new(Arc::clone(&jwt_key_repo), Arc::clone(&jwt_settings), configured_key)
+        .map_err(|e| {
+            error!("❌ Failed to load JWT signing keys: {}", e);
+            std::io::Error::other("Failed to load JWT signing keys")
+        })?);
+    let key_ring = jwt_key_service.key_ring();
+    let acl_cache_ttl_secs: u64 = std::env::var("ACL_CACHE_TTL_SECONDS")
+        .map(|v| v.parse().expect("❌ Environment variable ACL_CACHE_TTL_SECONDS must be a number of seconds"))
+        .unwrap_or(60);
+    let acl_cache_max_entries: usize = std::env::var("ACL_CACHE_MAX_ENTRIES")
+        .map(|v| v.parse().expect("❌ Environment variable ACL_CACHE_MAX_ENTRIES must be a number"))
+        .unwrap_or(10_000);
+    let acl_cache = Arc::new(AclDecisionCache::new(Duration::from_secs(acl_cache_ttl_secs), acl_cache_max_entries));
+    let active_key = key_ring.active();
+    info!("🟢 JWT signing key loaded: alg={:?} kid={}", active_key.algorithm, active_key.kid);
+    let user_locks = Arc::new(UserLocks::new());
+    let create_mqtt_service = Arc::new(CreateMqttService::new(Arc::clone(&create_mqtt_repo), Arc::clone(&get_by_username_repo), Arc::clone(&acl_cache), Arc::clone(&user_locks)));
     let get_mqtt_list_service = Arc::new(GetMqttListService::new(Arc::clone(&get_mqtt_list_repo)));
-    let mqtt_login_service = Arc::new(MqttLoginService::new(Arc::clone(&get_by_username_repo), secret_key));
-    let mqtt_acl_service = Arc::new(MqttAclService::new(Arc::clone(&get_by_username_repo)));
-    let soft_delete_mqtt_service = Arc::new(SoftDeleteMqttService::new(Arc::clone(&get_by_username_repo), Arc::clone(&soft_delete_mqtt_repo)));
+    let mqtt_acl_service = Arc::new(MqttAclService::new(Arc::clone(&get_by_username_repo), Arc::clone(&acl_rule_repo), Arc::clone(&acl_group_repo), Arc::clone(&acl_settings), Arc::clone(&acl_cache)));
+    let token_revocation_service = Arc::new(TokenRevocationService::new(Arc::clone(&token_revocation_repo), Arc::clone(&get_by_username_repo), Arc::clone(&key_ring), Arc::clone(&jwt_settings)));
+    let refresh_token_service = Arc::new(RefreshTokenService::new(Arc::clone(&refresh_token_repo), Arc::clone(&get_by_username_repo), Arc::clone(&key_ring), Arc::clone(&jwt_settings), Arc::clone(&mqtt_acl_service), Arc::clone(&token_revocation_service)));
+    let client_id_service = Arc::new(ClientIdService::new(Arc::clone(&get_by_username_repo), Arc::clone(&update_mqtt_repo), Arc::clone(&user_locks)));
+    let mqtt_login_service = Arc::new(MqttLoginService::new(Arc::clone(&get_by_username_repo), Arc::clone(&update_mqtt_repo), Arc::clone(&key_ring), Arc::clone(&jwt_settings), Arc::clone(&mqtt_acl_service), Arc::clone(&refresh_token_service), Arc::clone(&token_revocation_service), Arc::clone(&client_id_service), Arc::clone(&user_locks)));
+    let soft_delete_mqtt_service = Arc::new(SoftDeleteMqttService::new(Arc::clone(&get_by_username_repo), Arc::clone(&soft_delete_mqtt_repo), Arc::clone(&token_revocation_service), Arc::clone(&acl_cache), Arc::clone(&user_locks)));
+    let jwks_service = Arc::new(JwksService::new(Arc::clone(&key_ring)));
+    let acl_rule_service = Arc::new(AclRuleService::new(Arc::clone(&acl_rule_repo), Arc::clone(&acl_group_repo), Arc::clone(&get_by_username_repo), Arc::clone(&acl_cache)));
+    let acl_group_service = Arc::new(AclGroupService::new(Arc::clone(&acl_group_repo), Arc::clone(&get_by_username_repo), Arc::clone(&acl_cache)));
+    let peer_network_service = Arc::new(PeerNetworkService::new(Arc::clone(&get_by_username_repo), Arc::clone(&update_mqtt_repo), Arc::clone(&acl_cache), Arc::clone(&user_locks)));
+    let presence_service = Arc::new(PresenceService::new(Arc::clone(&presence_repo), Arc::clone(&get_by_username_repo)));
+    let token_introspection_service = Arc::new(TokenIntrospectionService::new(Arc::clone(&get_by_username_repo), Arc::clone(&key_ring), Arc::clone(&jwt_settings), Arc::clone(&token_revocation_service)));
 
     // =====================
     // 🚀 App State
     // =====================
     let create_mqtt_state = web::Data::new(CreateMqttAppState { create_mqtt_service });
     let get_mqtt_list_state = web::Data::new(GetListAppState { get_mqtt_list_service });
-    let mqtt_login_state = web::Data::new(MqttLoginAppState { mqtt_login_service });
-    let mqtt_acl_state = web::Data::new(MqttAclAppState { mqtt_acl_service });
+    let mqtt_login_state = web::Data::new(MqttLoginAppState { mqtt_login_service, response_format });
+    let mqtt_acl_state = web::Data::new(MqttAclAppState { mqtt_acl_service, response_format });
     let soft_delete_mqtt_state = web::Data::new(SoftDeleteMqttAppState { soft_delete_mqtt_service });
+    let jwks_state = web::Data::new(JwksAppState { jwks_service });
+    let refresh_token_state = web::Data::new(RefreshTokenAppState { refresh_token_service: Arc::clone(&refresh_token_service) });
+    let token_revocation_state = web::Data::new(TokenRevocationAppState { token_revocation_service: Arc::clone(&token_revocation_service) });
+    let jwt_key_state = web::Data::new(JwtKeyAppState { jwt_key_service: Arc::clone(&jwt_key_service) });
+    let token_introspection_state = web::Data::new(TokenIntrospectionAppState { token_introspection_service });
+    let acl_rule_state = web::Data::new(AclRuleAppState { acl_rule_service: Arc::clone(&acl_rule_service) });
+    let acl_group_state = web::Data::new(AclGroupAppState { acl_group_service });
+    let client_id_state = web::Data::new(ClientIdAppState { client_id_service });
+    let peer_network_state = web::Data::new(PeerNetworkAppState { peer_network_service });
+    let presence_state = web::Data::new(PresenceAppState { presence_service });
+
+    // =====================
+    // 🧽 Background Jobs
+    // =====================
+    let maintenance_interval_secs: u64 = std::env::var("MAINTENANCE_INTERVAL_SECONDS")
+        .map(|v| v.parse().ok()
+            .filter(|secs| *secs > 0)
+            .expect("❌ Environment variable MAINTENANCE_INTERVAL_SECONDS must be a positive number of seconds"))
+        .unwrap_or(300);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(maintenance_interval_secs));
+        loop {
+            interval.tick().await;
+            match token_revocation_service.prune_expired() {
+                Ok(removed) if removed > 0 => info!("🧽 Pruned {} expired token revocation entries", removed),
+                Ok(_) => {}
+                Err(e) => error!("❌ Failed to prune token revocation entries: {}", e),
+            }
+            match refresh_token_service.prune_expired() {
+                Ok(removed) if removed > 0 => info!("🧽 Pruned {} expired refresh token entries", removed),
+                Ok(_) => {}
+                Err(e) => error!("❌ Failed to prune refresh token entries: {}", e),
+            }
+            match jwt_key_service.prune_retired() {
+                Ok(removed) if removed > 0 => info!("🧽 Pruned {} retired JWT signing keys", removed),
+                Ok(_) => {}
+                Err(e) => error!("❌ Failed to prune retired JWT signing keys: {}", e),
+            }
+            match acl_rule_service.purge_expired() {
+                Ok(removed) if removed > 0 => info!("🧽 Purged {} expired ACL rules", removed),
+                Ok(_) => {}
+                Err(e) => error!("❌ Failed to purge expired ACL rules: {}", e),
+            }
+        }
+    });
 
     // =====================
     // 🌐 Start Server
@@ -120,12 +328,26 @@ pub async fn run_server() -> std::io::Result<()> {
             .app_data(mqtt_login_state.clone())
             .app_data(mqtt_acl_state.clone())
             .app_data(soft_delete_mqtt_state.clone())
+            .app_data(jwks_state.clone())
+            .app_data(refresh_token_state.clone())
+            .app_data(token_revocation_state.clone())
+            .app_data(jwt_key_state.clone())
+            .app_data(token_introspection_state.clone())
+            .app_data(acl_rule_state.clone())
+            .app_data(acl_group_state.clone())
+            .app_data(client_id_state.clone())
+            .app_data(peer_network_state.clone())
+            .app_data(presence_state.clone())
+            .app_data(field_mapping.clone())
             .wrap(PoweredByMiddleware)
             .wrap(RequestLoggerMiddleware)
             .wrap(middleware::Compress::default())
             
             // 🩺 Root API — health check
             .route("/", web::get().to(healthcheck))
+
+            // 🔑 Public signing keys for EMQX's JWT authenticator
+            .route("/.well-known/jwks.json", web::get().to(jwks_handler))
             
             // 👥 Mqtt endpoints
             .service(
@@ -133,7 +355,41 @@ pub async fn run_server() -> std::io::Result<()> {
                     .wrap(ApiKeyMiddleware)
                     .route("/create", web::post().to(create_mqtt_handler))
                     .route("/check", web::post().to(login_with_credentials_handler))
+                    .route("/check", web::get().to(login_with_credentials_handler))
                     .route("/acl", web::post().to(mqtt_acl_handler))
+                    .route("/acl", web::get().to(mqtt_acl_handler))
+                    .route("/acl/explain", web::post().to(explain_acl_handler))
+                    .route("/acl/cache", web::get().to(get_acl_cache_stats_handler))
+                    .route("/token/refresh", web::post().to(refresh_token_handler))
+                    .route("/token/revoke", web::post().to(revoke_token_handler))
+                    .route("/token/introspect", web::post().to(introspect_token_handler))
+                    .route("/keys", web::get().to(get_jwt_key_list_handler))
+                    .route("/keys/rotate", web::post().to(rotate_jwt_key_handler))
+                    .route("/events", web::post().to(record_event_handler))
+                    .route("/presence", web::get().to(get_presence_list_handler))
+                    .route("/presence/online", web::get().to(get_online_list_handler))
+                    .route("/presence/offline", web::get().to(get_offline_list_handler))
+                    .route("/groups", web::get().to(get_acl_group_list_handler))
+                    .route("/groups", web::post().to(create_acl_group_handler))
+                    .route("/groups/{group}", web::delete().to(delete_acl_group_handler))
+                    .route("/groups/{group}/acl", web::get().to(get_acl_group_rule_list_handler))
+                    .route("/groups/{group}/acl", web::post().to(create_acl_group_rule_handler))
+                    .route("/groups/{group}/acl/{rule_id}", web::put().to(update_acl_group_rule_handler))
+                    .route("/groups/{group}/acl/{rule_id}", web::delete().to(delete_acl_group_rule_handler))
+                    .route("/{username}/tokens", web::delete().to(revoke_all_tokens_handler))
+                    .route("/{username}/acl", web::get().to(get_acl_rule_list_handler))
+                    .route("/{username}/acl", web::post().to(create_acl_rule_handler))
+                    .route("/{username}/acl/{rule_id}", web::put().to(update_acl_rule_handler))
+                    .route("/{username}/acl/{rule_id}", web::delete().to(delete_acl_rule_handler))
+                    .route("/{username}/groups", web::get().to(get_user_groups_handler))
+                    .route("/{username}/groups", web::put().to(set_user_groups_handler))
+                    .route("/{username}/groups/{group}", web::post().to(add_user_group_handler))
+                    .route("/{username}/groups/{group}", web::delete().to(remove_user_group_handler))
+                    .route("/{username}/clientids", web::get().to(get_client_ids_handler))
+                    .route("/{username}/clientids", web::put().to(set_client_ids_handler))
+                    .route("/{username}/networks", web::get().to(get_networks_handler))
+                    .route("/{username}/networks", web::put().to(set_networks_handler))
+                    .route("/{username}/presence", web::get().to(get_presence_handler))
                     .route("/{username}", web::delete().to(soft_delete_mqtt))
 
                     // Development only
@@ -156,6 +412,13 @@ pub async fn run_server() -> std::io::Result<()> {
     drop(get_mqtt_list_repo);
     drop(get_by_username_repo);
     drop(soft_delete_mqtt_repo);
+    drop(update_mqtt_repo);
+    drop(refresh_token_repo);
+    drop(token_revocation_repo);
+    drop(jwt_key_repo);
+    drop(acl_rule_repo);
+    drop(acl_group_repo);
+    drop(presence_repo);
 
     info!("Closing RocksDB at {}", db_path);
     close_rocksdb(db);
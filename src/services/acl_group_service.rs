@@ -0,0 +1,174 @@
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use log::debug;
+use crate::dtos::acl_dto::{AclGroupDTO, CreateAclGroupDTO, UserGroupsDTO};
+use crate::entities::acl_group_entity::{AclGroupEntity, UserGroupsEntity};
+use crate::repositories::acl_group_repository::AclGroupRepository;
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::acl_cache::AclDecisionCache;
+
+const MAX_GROUP_NAME_LEN: usize = 64;
+
+pub struct AclGroupService {
+    repo_group: Arc<AclGroupRepository>,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    acl_cache: Arc<AclDecisionCache>,
+    // Serializes membership edits and group deletion so neither loses the other's write.
+    write_lock: Mutex<()>,
+}
+
+impl AclGroupService {
+    pub fn new(
+        repo_group: Arc<AclGroupRepository>,
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        acl_cache: Arc<AclDecisionCache>,
+    ) -> Self {
+        Self { repo_group, repo_get, acl_cache, write_lock: Mutex::new(()) }
+    }
+
+    pub fn create_group(&self, dto: CreateAclGroupDTO) -> Result<AclGroupDTO, MqttServiceError> {
+        Self::validate_group_name(&dto.name)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if self.repo_group.get_group(&dto.name)?.is_some() {
+            debug!("[Service | AclGroup] ACL group already exists: {}", dto.name);
+            return Err(MqttServiceError::Conflict("ACL group already exists".into()));
+        }
+
+        let group = AclGroupEntity::create(dto.name, Utc::now().timestamp());
+        self.repo_group.create_group(&group)?;
+        debug!("[Service | AclGroup] ACL group created: {}", group.name);
+        Ok(Self::to_dto(&group))
+    }
+
+    pub fn get_group_list(&self) -> Result<Vec<AclGroupDTO>, MqttServiceError> {
+        let groups = self.repo_group.get_group_list()?;
+        debug!("[Service | AclGroup] {} ACL groups retrieved successfully.", groups.len());
+        Ok(groups.iter().map(Self::to_dto).collect())
+    }
+
+    /// Delete a group together with its rules and memberships.
+    pub fn delete_group(&self, name: &str) -> Result<bool, MqttServiceError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.ensure_group_exists(name)?;
+        self.repo_group.delete_group(name)?;
+        self.acl_cache.clear();
+        debug!("[Service | AclGroup] ACL group deleted: {}", name);
+        Ok(true)
+    }
+
+    pub fn get_user_groups(&self, username: &str) -> Result<Vec<String>, MqttServiceError> {
+        self.ensure_user_exists(username)?;
+        Ok(self.repo_group.get_user_groups(username)?)
+    }
+
+    /// Replace the user's memberships; the order given is the evaluation order.
+    pub fn set_user_groups(&self, username: &str, dto: UserGroupsDTO) -> Result<Vec<String>, MqttServiceError> {
+        self.ensure_user_exists(username)?;
+
+        let mut groups: Vec<String> = Vec::with_capacity(dto.groups.len());
+        for group in dto.groups {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        for group in &groups {
+            self.ensure_group_exists(group)?;
+        }
+        self.save_user_groups(username, groups)
+    }
+
+    /// Append `group` to the user's memberships, evaluated after the existing ones.
+    pub fn add_user_group(&self, username: &str, group: &str) -> Result<Vec<String>, MqttServiceError> {
+        self.ensure_user_exists(username)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.ensure_group_exists(group)?;
+        let mut groups = self.repo_group.get_user_groups(username)?;
+        if !groups.iter().any(|g| g == group) {
+            groups.push(group.to_string());
+        }
+        self.save_user_groups(username, groups)
+    }
+
+    pub fn remove_user_group(&self, username: &str, group: &str) -> Result<Vec<String>, MqttServiceError> {
+        self.ensure_user_exists(username)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut groups = self.repo_group.get_user_groups(username)?;
+        let before = groups.len();
+        groups.retain(|g| g != group);
+        if groups.len() == before {
+            debug!("[Service | AclGroup] User MQTT {} is not a member of ACL group {}", username, group);
+            return Err(MqttServiceError::MqttNotFound("User MQTT is not a member of the ACL group".into()));
+        }
+        self.save_user_groups(username, groups)
+    }
+
+    fn save_user_groups(&self, username: &str, groups: Vec<String>) -> Result<Vec<String>, MqttServiceError> {
+        let membership = UserGroupsEntity { username: username.to_string(), groups };
+        self.repo_group.save_user_groups(&membership)?;
+        self.acl_cache.invalidate_user(username);
+        debug!("[Service | AclGroup] User MQTT {} now belongs to {} ACL groups", username, membership.groups.len());
+        Ok(membership.groups)
+    }
+
+    fn ensure_user_exists(&self, username: &str) -> Result<(), MqttServiceError> {
+        if username.trim().is_empty() {
+            return Err(MqttServiceError::BadRequest(vec![ValidationError {
+                field: "username".to_string(),
+                message: "username cannot be empty".to_string(),
+            }]));
+        }
+
+        match self.repo_get.get_by_username(username)? {
+            Some(_) => Ok(()),
+            None => {
+                debug!("[Service | AclGroup] User MQTT not found: {}", username);
+                Err(MqttServiceError::MqttNotFound("User MQTT not found".into()))
+            }
+        }
+    }
+
+    fn ensure_group_exists(&self, name: &str) -> Result<(), MqttServiceError> {
+        match self.repo_group.get_group(name)? {
+            Some(_) => Ok(()),
+            None => {
+                debug!("[Service | AclGroup] ACL group not found: {}", name);
+                Err(MqttServiceError::MqttNotFound(format!("ACL group `{}` not found", name)))
+            }
+        }
+    }
+
+    fn to_dto(group: &AclGroupEntity) -> AclGroupDTO {
+        AclGroupDTO {
+            name: group.name.clone(),
+            created_at: group.created_at,
+        }
+    }
+
+    fn validate_group_name(name: &str) -> Result<bool, MqttServiceError> {
+        let mut errors = Vec::new();
+        if name.is_empty() || name.len() > MAX_GROUP_NAME_LEN {
+            errors.push(ValidationError {
+                field: "name".to_string(),
+                message: format!("name must be between 1 and {} characters", MAX_GROUP_NAME_LEN),
+            });
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+            errors.push(ValidationError {
+                field: "name".to_string(),
+                message: "name may only contain letters, digits, `_`, `-` and `.`".to_string(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | AclGroup] ACL group input validation passed.");
+        Ok(true)
+    }
+}
@@ -0,0 +1,328 @@
+use std::sync::{Arc, Mutex};
+use chrono::{NaiveTime, Timelike, Utc};
+use log::debug;
+use uuid::Uuid;
+use crate::dtos::acl_dto::{AclActionType, AclPermissionType, AclRuleDTO, AclScheduleDTO, AclWeekdayType, SaveAclRuleDTO};
+use crate::entities::acl_rule_entity::{AclAction, AclPermission, AclRuleEntity, AclScheduleEntity, AclValidityEntity};
+use crate::repositories::acl_group_repository::AclGroupRepository;
+use crate::repositories::acl_rule_repository::AclRuleRepository;
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::acl_cache::AclDecisionCache;
+use crate::utils::topic_filter::{validate_placeholders, validate_topic_filter};
+
+/// Whose rule list an operation targets.
+#[derive(Clone, Copy)]
+pub enum AclRuleOwner<'a> {
+    User(&'a str),
+    Group(&'a str),
+}
+
+impl std::fmt::Display for AclRuleOwner<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::User(username) => write!(f, "user MQTT {}", username),
+            Self::Group(group) => write!(f, "ACL group {}", group),
+        }
+    }
+}
+
+// Indexed by the stored day number, which counts from Monday.
+const WEEKDAYS: [AclWeekdayType; 7] = [
+    AclWeekdayType::Mon,
+    AclWeekdayType::Tue,
+    AclWeekdayType::Wed,
+    AclWeekdayType::Thu,
+    AclWeekdayType::Fri,
+    AclWeekdayType::Sat,
+    AclWeekdayType::Sun,
+];
+
+pub struct AclRuleService {
+    repo_rule: Arc<AclRuleRepository>,
+    repo_group: Arc<AclGroupRepository>,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    acl_cache: Arc<AclDecisionCache>,
+    // Serializes read-modify-write of a rule list so concurrent edits are not lost.
+    write_lock: Mutex<()>,
+}
+
+impl AclRuleService {
+    pub fn new(
+        repo_rule: Arc<AclRuleRepository>,
+        repo_group: Arc<AclGroupRepository>,
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        acl_cache: Arc<AclDecisionCache>,
+    ) -> Self {
+        Self { repo_rule, repo_group, repo_get, acl_cache, write_lock: Mutex::new(()) }
+    }
+
+    pub fn get_rule_list(&self, owner: AclRuleOwner) -> Result<Vec<AclRuleDTO>, MqttServiceError> {
+        let rules = self.load_rules(owner)?;
+        debug!("[Service | AclRule] {} ACL rules retrieved for {}", rules.len(), owner);
+        Ok(rules.iter().map(Self::to_dto).collect())
+    }
+
+    pub fn create_rule(&self, owner: AclRuleOwner, dto: SaveAclRuleDTO) -> Result<AclRuleDTO, MqttServiceError> {
+        let validity = self.save_rule_validation(&dto)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rules = self.load_rules(owner)?;
+        let rule = AclRuleEntity::create(
+            Uuid::new_v4().to_string(),
+            Self::to_permission(dto.permission),
+            dto.topic,
+            Self::to_action(dto.action),
+            Self::normalize_qos(dto.qos),
+            dto.retain,
+            validity,
+        );
+        let position = dto.position.unwrap_or(rules.len()).min(rules.len());
+        rules.insert(position, rule.clone());
+        self.store_rules(owner, &rules)?;
+
+        debug!("[Service | AclRule] ACL rule {} created at position {} for {}", rule.id, position, owner);
+        Ok(Self::to_dto(&rule))
+    }
+
+    pub fn update_rule(&self, owner: AclRuleOwner, rule_id: &str, dto: SaveAclRuleDTO) -> Result<AclRuleDTO, MqttServiceError> {
+        let validity = self.save_rule_validation(&dto)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rules = self.load_rules(owner)?;
+        let Some(current) = rules.iter().position(|r| r.id == rule_id) else {
+            debug!("[Service | AclRule] ACL rule {} not found for {}", rule_id, owner);
+            return Err(MqttServiceError::MqttNotFound("ACL rule not found".into()));
+        };
+
+        let mut rule = rules.remove(current);
+        rule.permission = Self::to_permission(dto.permission);
+        rule.topic = dto.topic;
+        rule.action = Self::to_action(dto.action);
+        rule.qos = Self::normalize_qos(dto.qos);
+        rule.retain = dto.retain;
+        rule.validity = validity;
+        let position = dto.position.unwrap_or(current).min(rules.len());
+        rules.insert(position, rule.clone());
+        self.store_rules(owner, &rules)?;
+
+        debug!("[Service | AclRule] ACL rule {} updated at position {} for {}", rule.id, position, owner);
+        Ok(Self::to_dto(&rule))
+    }
+
+    pub fn delete_rule(&self, owner: AclRuleOwner, rule_id: &str) -> Result<bool, MqttServiceError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rules = self.load_rules(owner)?;
+        let before = rules.len();
+        rules.retain(|r| r.id != rule_id);
+        if rules.len() == before {
+            debug!("[Service | AclRule] ACL rule {} not found for {}", rule_id, owner);
+            return Err(MqttServiceError::MqttNotFound("ACL rule not found".into()));
+        }
+        self.store_rules(owner, &rules)?;
+
+        debug!("[Service | AclRule] ACL rule {} deleted for {}", rule_id, owner);
+        Ok(true)
+    }
+
+    /// Remove rules whose `valid_until` has passed from every user and group.
+    pub fn purge_expired(&self) -> Result<usize, MqttServiceError> {
+        let now = Utc::now().timestamp();
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut purged = 0;
+        for (username, rules) in self.repo_rule.get_all_rules()? {
+            purged += self.purge_rules(AclRuleOwner::User(&username), rules, now)?;
+        }
+        for (group, rules) in self.repo_rule.get_all_group_rules()? {
+            purged += self.purge_rules(AclRuleOwner::Group(&group), rules, now)?;
+        }
+        Ok(purged)
+    }
+
+    fn purge_rules(&self, owner: AclRuleOwner, mut rules: Vec<AclRuleEntity>, now: i64) -> Result<usize, MqttServiceError> {
+        let before = rules.len();
+        rules.retain(|r| r.validity.valid_until.is_none_or(|t| t > now));
+        let purged = before - rules.len();
+        if purged > 0 {
+            self.store_rules(owner, &rules)?;
+            debug!("[Service | AclRule] {} expired ACL rules purged for {}", purged, owner);
+        }
+        Ok(purged)
+    }
+
+    /// Load the owner's rules, failing with 404 when the user or group does not exist.
+    fn load_rules(&self, owner: AclRuleOwner) -> Result<Vec<AclRuleEntity>, MqttServiceError> {
+        match owner {
+            AclRuleOwner::User(username) => {
+                Self::validate_owner_name("username", username)?;
+                if self.repo_get.get_by_username(username)?.is_none() {
+                    debug!("[Service | AclRule] User MQTT not found: {}", username);
+                    return Err(MqttServiceError::MqttNotFound("User MQTT not found".into()));
+                }
+                Ok(self.repo_rule.get_rules(username)?)
+            }
+            AclRuleOwner::Group(group) => {
+                Self::validate_owner_name("group", group)?;
+                if self.repo_group.get_group(group)?.is_none() {
+                    debug!("[Service | AclRule] ACL group not found: {}", group);
+                    return Err(MqttServiceError::MqttNotFound("ACL group not found".into()));
+                }
+                Ok(self.repo_rule.get_group_rules(group)?)
+            }
+        }
+    }
+
+    fn store_rules(&self, owner: AclRuleOwner, rules: &[AclRuleEntity]) -> Result<(), MqttServiceError> {
+        match owner {
+            AclRuleOwner::User(username) => {
+                self.repo_rule.save_rules(username, rules)?;
+                self.acl_cache.invalidate_user(username);
+            }
+            AclRuleOwner::Group(group) => {
+                self.repo_rule.save_group_rules(group, rules)?;
+                // Any number of users may belong to the group.
+                self.acl_cache.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_owner_name(field: &str, name: &str) -> Result<(), MqttServiceError> {
+        if name.trim().is_empty() {
+            return Err(MqttServiceError::BadRequest(vec![ValidationError {
+                field: field.to_string(),
+                message: format!("{} cannot be empty", field),
+            }]));
+        }
+        Ok(())
+    }
+
+    fn to_permission(permission: AclPermissionType) -> AclPermission {
+        match permission {
+            AclPermissionType::Allow => AclPermission::Allow,
+            AclPermissionType::Deny => AclPermission::Deny,
+        }
+    }
+
+    fn to_action(action: AclActionType) -> AclAction {
+        match action {
+            AclActionType::All => AclAction::All,
+            AclActionType::Publish => AclAction::Publish,
+            AclActionType::Subscribe => AclAction::Subscribe,
+        }
+    }
+
+    fn normalize_qos(mut qos: Vec<u8>) -> Vec<u8> {
+        qos.sort_unstable();
+        qos.dedup();
+        qos
+    }
+
+    pub fn to_dto(rule: &AclRuleEntity) -> AclRuleDTO {
+        AclRuleDTO {
+            id: rule.id.clone(),
+            permission: match rule.permission {
+                AclPermission::Allow => AclPermissionType::Allow,
+                AclPermission::Deny => AclPermissionType::Deny,
+            },
+            topic: rule.topic.clone(),
+            action: match rule.action {
+                AclAction::All => AclActionType::All,
+                AclAction::Publish => AclActionType::Publish,
+                AclAction::Subscribe => AclActionType::Subscribe,
+            },
+            qos: rule.qos.clone(),
+            retain: rule.retain,
+            valid_from: rule.validity.valid_from,
+            valid_until: rule.validity.valid_until,
+            schedule: rule.validity.schedule.as_ref().map(|schedule| AclScheduleDTO {
+                days: schedule.days.iter().map(|d| WEEKDAYS[*d as usize]).collect(),
+                start: Self::format_minutes(schedule.start),
+                end: Self::format_minutes(schedule.end),
+            }),
+        }
+    }
+
+    fn parse_minutes(time: &str) -> Option<u16> {
+        let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+        Some((time.hour() * 60 + time.minute()) as u16)
+    }
+
+    fn format_minutes(minutes: u16) -> String {
+        format!("{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+
+    fn save_rule_validation(&self, dto: &SaveAclRuleDTO) -> Result<AclValidityEntity, MqttServiceError> {
+        let mut errors = Vec::new();
+        if let Err(message) = validate_topic_filter(&dto.topic).and_then(|_| validate_placeholders(&dto.topic)) {
+            errors.push(ValidationError {
+                field: "topic".to_string(),
+                message: message.to_string(),
+            });
+        }
+
+        if dto.qos.iter().any(|q| *q > 2) {
+            errors.push(ValidationError {
+                field: "qos".to_string(),
+                message: "qos levels must be 0, 1 or 2".to_string(),
+            });
+        }
+
+        if dto.retain.is_some() && dto.action == AclActionType::Subscribe {
+            errors.push(ValidationError {
+                field: "retain".to_string(),
+                message: "retain only applies to publish rules".to_string(),
+            });
+        }
+
+        match (dto.valid_from, dto.valid_until) {
+            (Some(from), Some(until)) if from >= until => errors.push(ValidationError {
+                field: "valid_until".to_string(),
+                message: "valid_until must be after valid_from".to_string(),
+            }),
+            (_, Some(until)) if until <= Utc::now().timestamp() => errors.push(ValidationError {
+                field: "valid_until".to_string(),
+                message: "valid_until must be in the future".to_string(),
+            }),
+            _ => {}
+        }
+
+        let schedule = dto.schedule.as_ref().and_then(|schedule| {
+            if schedule.days.is_empty() {
+                errors.push(ValidationError {
+                    field: "schedule.days".to_string(),
+                    message: "schedule needs at least one day".to_string(),
+                });
+            }
+            let start = Self::parse_minutes(&schedule.start);
+            let end = Self::parse_minutes(&schedule.end);
+            for (field, parsed) in [("schedule.start", start), ("schedule.end", end)] {
+                if parsed.is_none() {
+                    errors.push(ValidationError {
+                        field: field.to_string(),
+                        message: "time must be given as HH:MM".to_string(),
+                    });
+                }
+            }
+            if start.is_some() && start == end {
+                errors.push(ValidationError {
+                    field: "schedule.end".to_string(),
+                    message: "schedule start and end cannot be the same time".to_string(),
+                });
+            }
+
+            let mut days: Vec<u8> = schedule.days.iter().map(|d| *d as u8).collect();
+            days.sort_unstable();
+            days.dedup();
+            Some(AclScheduleEntity { days, start: start?, end: end? })
+        });
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | AclRule] ACL rule input validation passed.");
+        Ok(AclValidityEntity { valid_from: dto.valid_from, valid_until: dto.valid_until, schedule })
+    }
+}
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+use log::debug;
+use crate::dtos::mqtt_dto::{ClientIdMatchType, ClientIdPatternDTO, MqttClientIdsDTO};
+use crate::entities::mqtt_entity::{ClientIdPatternEntity, MqttEntity};
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::update_mqtt_repository::UpdateMqttRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::client_id::{client_id_matches, compile_regex};
+use crate::utils::user_lock::UserLocks;
+
+const MAX_PATTERN_LEN: usize = 256;
+
+pub struct ClientIdService {
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    repo_update: Arc<UpdateMqttRepository>,
+    // Also serializes first-use binding, so a concurrent login cannot bind twice.
+    user_locks: Arc<UserLocks>,
+}
+
+impl ClientIdService {
+    pub fn new(
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        repo_update: Arc<UpdateMqttRepository>,
+        user_locks: Arc<UserLocks>,
+    ) -> Self {
+        Self { repo_get, repo_update, user_locks }
+    }
+
+    pub fn get_client_ids(&self, username: &str) -> Result<MqttClientIdsDTO, MqttServiceError> {
+        let mqtt = self.get_user(username)?;
+        Ok(Self::to_dto(&mqtt))
+    }
+
+    /// Replace the user's allowed client IDs. An empty list with `bind_first` unset allows any
+    /// client ID; with `bind_first` set it re-arms binding on the next login.
+    pub fn set_client_ids(&self, username: &str, dto: MqttClientIdsDTO) -> Result<MqttClientIdsDTO, MqttServiceError> {
+        let patterns = Self::client_ids_validation(dto.patterns)?;
+
+        let _guard = self.user_locks.lock(username);
+        let mut mqtt = self.get_user(username)?;
+        mqtt.client_ids = patterns;
+        mqtt.bind_client_id = dto.bind_first;
+        self.repo_update.update_mqtt(&mqtt)?;
+        debug!("[Service | ClientId] User MQTT {} now allows {} client ID patterns", username, mqtt.client_ids.len());
+        Ok(Self::to_dto(&mqtt))
+    }
+
+    /// Check the client ID EMQX is authenticating against the user's patterns, binding it
+    /// first if the user is set to trust the first client ID it sees.
+    pub fn verify_client_id(&self, mqtt: &MqttEntity, client_id: Option<&str>) -> Result<(), MqttServiceError> {
+        if mqtt.client_ids.is_empty() && !mqtt.bind_client_id {
+            return Ok(());
+        }
+
+        let Some(client_id) = client_id.filter(|c| !c.is_empty()) else {
+            debug!("[Service | ClientId] No client ID given for restricted user MQTT: {}", mqtt.username);
+            return Err(MqttServiceError::InvalidCredentials("Client ID is required for this user".into()));
+        };
+
+        if mqtt.client_ids.is_empty() {
+            return self.bind_client_id(&mqtt.username, client_id);
+        }
+
+        Self::ensure_allowed(&mqtt.username, &mqtt.client_ids, client_id)
+    }
+
+    fn bind_client_id(&self, username: &str, client_id: &str) -> Result<(), MqttServiceError> {
+        let _guard = self.user_locks.lock(username);
+        // Re-read under the lock: another login may have bound a client ID in the meantime.
+        let mut mqtt = self.get_user(username)?;
+        if !mqtt.client_ids.is_empty() {
+            return Self::ensure_allowed(username, &mqtt.client_ids, client_id);
+        }
+        if !mqtt.bind_client_id {
+            return Ok(());
+        }
+
+        mqtt.client_ids.push(ClientIdPatternEntity::Exact(client_id.to_string()));
+        self.repo_update.update_mqtt(&mqtt)?;
+        debug!("[Service | ClientId] User MQTT {} bound to client ID {}", username, client_id);
+        Ok(())
+    }
+
+    fn ensure_allowed(username: &str, patterns: &[ClientIdPatternEntity], client_id: &str) -> Result<(), MqttServiceError> {
+        if patterns.iter().any(|p| client_id_matches(p, client_id)) {
+            return Ok(());
+        }
+        debug!("[Service | ClientId] Client ID {} is not allowed for user MQTT: {}", client_id, username);
+        Err(MqttServiceError::InvalidCredentials("Client ID is not allowed for this user".into()))
+    }
+
+    fn client_ids_validation(patterns: Vec<ClientIdPatternDTO>) -> Result<Vec<ClientIdPatternEntity>, MqttServiceError> {
+        let mut errors = Vec::new();
+        let mut entities = Vec::with_capacity(patterns.len());
+        for (i, dto) in patterns.into_iter().enumerate() {
+            let field = format!("patterns[{}].pattern", i);
+            if dto.pattern.is_empty() || dto.pattern.len() > MAX_PATTERN_LEN {
+                errors.push(ValidationError {
+                    field,
+                    message: format!("pattern must be between 1 and {} characters", MAX_PATTERN_LEN),
+                });
+                continue;
+            }
+            entities.push(match dto.match_type {
+                ClientIdMatchType::Exact => ClientIdPatternEntity::Exact(dto.pattern),
+                ClientIdMatchType::Glob => ClientIdPatternEntity::Glob(dto.pattern),
+                ClientIdMatchType::Regex => match compile_regex(&dto.pattern) {
+                    Ok(_) => ClientIdPatternEntity::Regex(dto.pattern),
+                    Err(e) => {
+                        errors.push(ValidationError { field, message: format!("invalid regex: {}", e) });
+                        continue;
+                    }
+                },
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+        Ok(entities)
+    }
+
+    fn get_user(&self, username: &str) -> Result<MqttEntity, MqttServiceError> {
+        match self.repo_get.get_by_username(username)? {
+            Some(mqtt) => Ok(mqtt),
+            None => {
+                debug!("[Service | ClientId] User MQTT not found: {}", username);
+                Err(MqttServiceError::MqttNotFound("User MQTT not found".into()))
+            }
+        }
+    }
+
+    fn to_dto(mqtt: &MqttEntity) -> MqttClientIdsDTO {
+        let patterns = mqtt.client_ids.iter().map(|p| {
+            let (match_type, pattern) = match p {
+                ClientIdPatternEntity::Exact(s) => (ClientIdMatchType::Exact, s),
+                ClientIdPatternEntity::Glob(s) => (ClientIdMatchType::Glob, s),
+                ClientIdPatternEntity::Regex(s) => (ClientIdMatchType::Regex, s),
+            };
+            ClientIdPatternDTO { match_type, pattern: pattern.clone() }
+        }).collect();
+        MqttClientIdsDTO { patterns, bind_first: mqtt.bind_client_id }
+    }
+}
@@ -5,27 +5,40 @@ use crate::repositories::create_mqtt_repository::CreateMqttRepository;
 use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
 use crate::services::service_error::{MqttServiceError, ValidationError};
 use crate::dtos::mqtt_dto::CreateMqttDTO;
+use crate::utils::acl_cache::AclDecisionCache;
 use crate::utils::hash_password::hash_password;
+use crate::utils::user_lock::UserLocks;
 
 pub struct CreateMqttService {
     repo_create: Arc<CreateMqttRepository>,
     repo_get: Arc<GetMqttByUsernameRepository>,
+    acl_cache: Arc<AclDecisionCache>,
+    user_locks: Arc<UserLocks>,
 }
 
 impl CreateMqttService {
-    pub fn new(repo_create: Arc<CreateMqttRepository>, repo_get: Arc<GetMqttByUsernameRepository>) -> Self {
-        Self { repo_create, repo_get }
+    pub fn new(
+        repo_create: Arc<CreateMqttRepository>,
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        acl_cache: Arc<AclDecisionCache>,
+        user_locks: Arc<UserLocks>,
+    ) -> Self {
+        Self { repo_create, repo_get, acl_cache, user_locks }
     }
 
     pub fn create_mqtt(&self, dto: CreateMqttDTO) -> Result<bool, MqttServiceError> {
         self.create_mqtt_validation(&dto)?;
-        
+
+        let hashed = hash_password(&dto.password)
+            .map_err(|e| MqttServiceError::PasswordHashError(e.to_string()))?;
+
+        let _guard = self.user_locks.lock(&dto.username);
         if self.repo_get.get_by_username(&dto.username)?.is_some() {
             return Err(MqttServiceError::Conflict("MQTT user already exists".into()));
         }
 
-        let hashed = hash_password(&dto.password);
         self.repo_create.create_mqtt(&dto.username, &hashed, dto.is_superuser)?;
+        self.acl_cache.invalidate_user(&dto.username);
         debug!("[Service | CreateMQTT] User MQTT created successfully: {}", &dto.username);
         Ok(true)
     }
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+use log::debug;
+use jsonwebtoken::jwk::JwkSet;
+use crate::utils::jwt_keys::KeyRing;
+
+pub struct JwksService {
+    key_ring: Arc<KeyRing>,
+}
+
+impl JwksService {
+    pub fn new(key_ring: Arc<KeyRing>) -> Self {
+        Self { key_ring }
+    }
+
+    pub fn get_jwks(&self) -> JwkSet {
+        let jwks = self.key_ring.jwks();
+        debug!("[Service | GetJWKS] {} public signing keys retrieved successfully.", jwks.keys.len());
+        jwks
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use jsonwebtoken::Algorithm;
+use jsonwebtoken::errors::ErrorKind;
+use log::{debug, info, warn};
+use uuid::Uuid;
+use crate::dtos::mqtt_dto::{JwtKeyDTO, RotateJwtKeyDTO};
+use crate::entities::jwt_key_entity::JwtKeyEntity;
+use crate::repositories::jwt_key_repository::JwtKeyRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::jwt_keys::{default_kid, parse_algorithm, JwtKey, KeyRing};
+use crate::utils::jwt_sign::JwtSettings;
+
+pub struct JwtKeyService {
+    repo: Arc<JwtKeyRepository>,
+    key_ring: Arc<KeyRing>,
+    jwt_settings: Arc<JwtSettings>,
+    // Serializes rotations so the persisted ring and the in-memory ring stay in step.
+    rotation_lock: Mutex<()>,
+}
+
+impl JwtKeyService {
+    /// Load the persisted key ring and reconcile it with the key configured in the environment:
+    /// an unknown configured key becomes active and retires the current one, a configured key
+    /// that was already rotated out keeps the promoted key active.
+    pub fn new(
+        repo: Arc<JwtKeyRepository>,
+        jwt_settings: Arc<JwtSettings>,
+        configured: JwtKeyEntity,
+    ) -> Result<Self, MqttServiceError> {
+        let now = Utc::now().timestamp();
+        let mut stored = repo.get_key_list()?;
+
+        match stored.iter().position(|k| k.kid == configured.kid) {
+            Some(i) if stored[i].is_active => {
+                stored[i] = JwtKeyEntity { created_at: stored[i].created_at, ..configured };
+            }
+            Some(_) if stored.iter().any(|k| k.is_active) => {
+                warn!("[Service | JwtKey] Configured signing key `{}` has been rotated out; keeping the promoted key active", configured.kid);
+            }
+            _ => {
+                for previous in stored.iter_mut().filter(|k| k.is_active) {
+                    info!("[Service | JwtKey] Configured signing key `{}` replaces `{}`", configured.kid, previous.kid);
+                    Self::retire(previous, now + jwt_settings.max_ttl_secs as i64);
+                }
+                stored.retain(|k| k.kid != configured.kid);
+                stored.push(configured);
+            }
+        }
+
+        for expired in stored.iter_mut().filter(|k| Self::is_expired(k, now)) {
+            Self::strip_material(expired);
+        }
+        repo.save_keys(&stored.iter().collect::<Vec<_>>())?;
+
+        let mut active = None;
+        let mut retired = Vec::new();
+        for entity in stored.iter().filter(|k| !Self::is_expired(k, now)) {
+            let key = Self::build_key(entity).map_err(|e| MqttServiceError::JwtError(format!("signing key `{}`: {}", entity.kid, e)))?;
+            match entity.verify_until {
+                _ if entity.is_active => active = Some(key),
+                Some(verify_until) => retired.push((key, verify_until)),
+                None => {}
+            }
+        }
+
+        let active = active.ok_or_else(|| MqttServiceError::JwtError("no active signing key".into()))?;
+        debug!("[Service | JwtKey] Key ring loaded: active `{}`, {} retired keys.", active.kid, retired.len());
+        let key_ring = Arc::new(KeyRing::new(active, retired));
+        Ok(Self { repo, key_ring, jwt_settings, rotation_lock: Mutex::new(()) })
+    }
+
+    pub fn key_ring(&self) -> Arc<KeyRing> {
+        Arc::clone(&self.key_ring)
+    }
+
+    /// Promote a new signing key. The previous key keeps verifying tokens until the longest
+    /// token it could have signed has expired.
+    pub fn rotate_key(&self, dto: RotateJwtKeyDTO) -> Result<JwtKeyDTO, MqttServiceError> {
+        let algorithm = self.rotate_key_validation(&dto)?;
+
+        let _guard = self.rotation_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now().timestamp();
+        let kid = match (&dto.kid, &dto.public_key, &dto.secret) {
+            (Some(kid), _, _) => kid.clone(),
+            (None, Some(public_key), _) => default_kid(public_key.as_bytes()),
+            (None, None, Some(secret)) => default_kid(secret.as_bytes()),
+            (None, None, None) => Uuid::new_v4().simple().to_string(),
+        };
+
+        if self.key_ring.contains(&kid) {
+            return Err(MqttServiceError::Conflict(format!("Signing key `{}` already exists", kid)));
+        }
+
+        let entity = JwtKeyEntity::create(&kid, format!("{:?}", algorithm), dto.secret, dto.private_key, dto.public_key, now);
+        let key = Self::build_key(&entity).map_err(|e| {
+            let (field, message) = match e.kind() {
+                ErrorKind::InvalidSignature => ("public_key", "public_key does not match private_key".to_string()),
+                _ => ("private_key", format!("invalid key material: {}", e)),
+            };
+            MqttServiceError::BadRequest(vec![ValidationError { field: field.to_string(), message }])
+        })?;
+
+        let verify_until = now + self.jwt_settings.max_ttl_secs as i64;
+        let mut previous: Vec<JwtKeyEntity> = self.repo.get_key_list()?.into_iter().filter(|k| k.is_active).collect();
+        for key in previous.iter_mut() {
+            Self::retire(key, verify_until);
+        }
+        let mut to_save: Vec<&JwtKeyEntity> = previous.iter().collect();
+        to_save.push(&entity);
+        self.repo.save_keys(&to_save)?;
+
+        self.key_ring.promote(key, verify_until);
+        info!("[Service | JwtKey] Signing key `{}` promoted to active", kid);
+        Ok(Self::to_dto(&entity))
+    }
+
+    pub fn get_key_list(&self) -> Result<Vec<JwtKeyDTO>, MqttServiceError> {
+        let now = Utc::now().timestamp();
+        let keys = self.repo.get_key_list()?
+            .iter()
+            .filter(|k| !Self::is_expired(k, now))
+            .map(Self::to_dto)
+            .collect();
+        debug!("[Service | JwtKey] Signing key list retrieved successfully.");
+        Ok(keys)
+    }
+
+    /// Forget retired keys whose verification window closed. Their records are kept without
+    /// key material so a restart with the old key still configured cannot resurrect it.
+    pub fn prune_retired(&self) -> Result<usize, MqttServiceError> {
+        let now = Utc::now().timestamp();
+        let pruned = self.key_ring.prune(now);
+        if pruned.is_empty() {
+            return Ok(0);
+        }
+
+        let mut stored = self.repo.get_key_list()?;
+        for entity in stored.iter_mut().filter(|k| pruned.contains(&k.kid)) {
+            Self::strip_material(entity);
+        }
+        self.repo.save_keys(&stored.iter().filter(|k| pruned.contains(&k.kid)).collect::<Vec<_>>())?;
+        debug!("[Service | JwtKey] Pruned {} retired signing keys.", pruned.len());
+        Ok(pruned.len())
+    }
+
+    fn build_key(entity: &JwtKeyEntity) -> Result<JwtKey, jsonwebtoken::errors::Error> {
+        let algorithm = parse_algorithm(&entity.algorithm)
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidAlgorithmName))?;
+        match (algorithm, &entity.secret, &entity.private_key_pem, &entity.public_key_pem) {
+            (Algorithm::HS256, Some(secret), _, _) => Ok(JwtKey::from_secret(&entity.kid, secret)),
+            (_, _, Some(private_pem), Some(public_pem)) => {
+                JwtKey::from_pem(Some(entity.kid.clone()), algorithm, private_pem.as_bytes(), public_pem.as_bytes())
+            }
+            _ => Err(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat.into()),
+        }
+    }
+
+    fn retire(entity: &mut JwtKeyEntity, verify_until: i64) {
+        entity.is_active = false;
+        entity.verify_until = Some(verify_until);
+    }
+
+    fn is_expired(entity: &JwtKeyEntity, now: i64) -> bool {
+        !entity.is_active && entity.verify_until.is_none_or(|t| t < now)
+    }
+
+    fn strip_material(entity: &mut JwtKeyEntity) {
+        entity.secret = None;
+        entity.private_key_pem = None;
+        entity.public_key_pem = None;
+    }
+
+    fn to_dto(entity: &JwtKeyEntity) -> JwtKeyDTO {
+        JwtKeyDTO {
+            kid: entity.kid.clone(),
+            algorithm: entity.algorithm.clone(),
+            is_active: entity.is_active,
+            created_at: entity.created_at,
+            verify_until: entity.verify_until,
+        }
+    }
+
+    fn rotate_key_validation(&self, dto: &RotateJwtKeyDTO) -> Result<Algorithm, MqttServiceError> {
+        let mut errors = Vec::new();
+        let algorithm = parse_algorithm(&dto.algorithm);
+        match algorithm {
+            None => errors.push(ValidationError {
+                field: "algorithm".to_string(),
+                message: "algorithm must be one of HS256, RS256, ES256, EdDSA".to_string(),
+            }),
+            Some(Algorithm::HS256) => {
+                if dto.secret.as_deref().is_none_or(|s| s.len() < 32) {
+                    errors.push(ValidationError {
+                        field: "secret".to_string(),
+                        message: "secret of at least 32 characters is required for HS256".to_string(),
+                    });
+                }
+            }
+            Some(_) => {
+                if dto.private_key.as_deref().is_none_or(|k| k.trim().is_empty()) {
+                    errors.push(ValidationError {
+                        field: "private_key".to_string(),
+                        message: "private_key PEM is required for asymmetric algorithms".to_string(),
+                    });
+                }
+                if dto.public_key.as_deref().is_none_or(|k| k.trim().is_empty()) {
+                    errors.push(ValidationError {
+                        field: "public_key".to_string(),
+                        message: "public_key PEM is required for asymmetric algorithms".to_string(),
+                    });
+                }
+            }
+        }
+
+        if dto.kid.as_deref().is_some_and(|k| k.trim().is_empty()) {
+            errors.push(ValidationError {
+                field: "kid".to_string(),
+                message: "kid cannot be empty".to_string(),
+            });
+        }
+
+        match algorithm {
+            Some(algorithm) if errors.is_empty() => {
+                debug!("[Service | JwtKey] Rotate key input validation passed.");
+                Ok(algorithm)
+            }
+            _ => Err(MqttServiceError::BadRequest(errors)),
+        }
+    }
+}
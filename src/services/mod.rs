@@ -3,4 +3,14 @@ pub mod create_mqtt_service;
 pub mod get_mqtt_list_service;
 pub mod mqtt_login_service;
 pub mod mqtt_acl_service;
-pub mod soft_delete_mqtt_service;
\ No newline at end of file
+pub mod soft_delete_mqtt_service;
+pub mod jwks_service;
+pub mod refresh_token_service;
+pub mod token_revocation_service;
+pub mod jwt_key_service;
+pub mod token_introspection_service;
+pub mod acl_rule_service;
+pub mod acl_group_service;
+pub mod client_id_service;
+pub mod peer_network_service;
+pub mod presence_service;
\ No newline at end of file
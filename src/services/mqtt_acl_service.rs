@@ -1,32 +1,148 @@
 use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono_tz::Tz;
 use log::debug;
+use crate::repositories::acl_group_repository::AclGroupRepository;
+use crate::repositories::acl_rule_repository::AclRuleRepository;
 use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::services::acl_rule_service::AclRuleService;
 use crate::services::service_error::{MqttServiceError, ValidationError};
-use crate::dtos::mqtt_dto::MqttAclDTO;
+use crate::dtos::acl_dto::{AclCacheStatsDTO, AclExplanationDTO, EvaluatedAclRuleDTO};
+use crate::dtos::mqtt_dto::{MqttAclAction, MqttAclDTO};
+use crate::dtos::jwt_dto::AclClaim;
+use crate::entities::acl_rule_entity::{AclAction, AclPermission, AclRuleEntity};
+use crate::entities::mqtt_entity::MqttEntity;
+use crate::utils::acl_cache::{AclCacheKey, AclDecisionCache};
+use crate::utils::acl_policy::{namespace_covers, AclDecision, AclMatchSource, AclSettings};
+use crate::utils::cidr::peer_allowed;
+use crate::utils::topic_filter::{expand_placeholders, parse_shared_subscription, topic_matches, topics_overlap};
+
+struct AclOutcome {
+    decision: AclDecision,
+    source: AclMatchSource,
+    rule: Option<AclRuleEntity>,
+    // Unix time at which a time-limited rule looked at may switch on or off, changing the decision.
+    changes_at: Option<i64>,
+}
+
+impl AclOutcome {
+    fn new(decision: AclDecision, source: AclMatchSource) -> Self {
+        Self { decision, source, rule: None, changes_at: None }
+    }
+
+    /// How long the decision holds, when a time-limited rule bounds it.
+    fn valid_for(&self) -> Option<Duration> {
+        self.changes_at.map(|t| {
+            let millis = t.saturating_mul(1000).saturating_sub(Utc::now().timestamp_millis());
+            Duration::from_millis(millis.max(0) as u64)
+        })
+    }
+}
 
 pub struct MqttAclService {
     repo: Arc<GetMqttByUsernameRepository>,
+    repo_rule: Arc<AclRuleRepository>,
+    repo_group: Arc<AclGroupRepository>,
+    settings: Arc<AclSettings>,
+    cache: Arc<AclDecisionCache>,
 }
 
 impl MqttAclService {
-    pub fn new(repo: Arc<GetMqttByUsernameRepository>) -> MqttAclService {
-        Self { repo }
+    pub fn new(
+        repo: Arc<GetMqttByUsernameRepository>,
+        repo_rule: Arc<AclRuleRepository>,
+        repo_group: Arc<AclGroupRepository>,
+        settings: Arc<AclSettings>,
+        cache: Arc<AclDecisionCache>,
+    ) -> MqttAclService {
+        Self { repo, repo_rule, repo_group, settings, cache }
+    }
+
+    pub fn check_acl_permission(&self, dto: MqttAclDTO) -> Result<AclDecision, MqttServiceError> {
+        self.mqtt_input_acl_validation(&dto)?;
+
+        let key = AclCacheKey::new(&dto);
+        let (decision, source) = match self.cache.get(&key) {
+            Some(cached) => {
+                debug!(
+                    "[Service | CheckMQTTACL] Cached ACL decision for user `{}` on topic `{}` → {}",
+                    dto.username, dto.topic, cached.0.as_str()
+                );
+                cached
+            }
+            None => {
+                let generation = self.cache.generation();
+                let outcome = self.evaluate(&dto, None)?;
+                self.cache.insert(key, outcome.decision, outcome.source.clone(), generation, outcome.valid_for());
+                (outcome.decision, outcome.source)
+            }
+        };
+
+        match source {
+            AclMatchSource::UnknownUser if decision == AclDecision::Deny => {
+                Err(MqttServiceError::MqttNotFound("User MQTT not found".into()))
+            }
+            AclMatchSource::DeletedUser => {
+                Err(MqttServiceError::MqttNotActive("User MQTT is not active or deleted".into()))
+            }
+            _ => Ok(decision),
+        }
     }
 
-    pub fn check_acl_permission(&self, dto: MqttAclDTO) -> Result<bool, MqttServiceError> {
+    pub fn get_cache_stats(&self) -> AclCacheStatsDTO {
+        self.cache.stats()
+    }
+
+    /// Dry run of `check_acl_permission` that reports what decided and every rule evaluated
+    /// on the way. Unknown and deleted users are explained rather than reported as errors.
+    /// The decision cache is neither read nor filled.
+    pub fn explain_acl_decision(&self, dto: MqttAclDTO) -> Result<AclExplanationDTO, MqttServiceError> {
         self.mqtt_input_acl_validation(&dto)?;
 
+        let mut evaluated_rules = Vec::new();
+        let outcome = self.evaluate(&dto, Some(&mut evaluated_rules))?;
+        debug!(
+            "[Service | CheckMQTTACL] ACL decision explained for user `{}` on topic `{}`: {} by {}",
+            dto.username, dto.topic, outcome.decision.as_str(), outcome.source.as_str()
+        );
+
+        Ok(AclExplanationDTO {
+            result: outcome.decision.as_str(),
+            source: outcome.source.as_str(),
+            group: outcome.source.group().map(str::to_string),
+            matched_rule: outcome.rule.as_ref().map(AclRuleService::to_dto),
+            share_group: parse_shared_subscription(&dto.topic).map(|s| s.group.to_string()),
+            evaluated_rules,
+        })
+    }
+
+    /// The single evaluation path behind both the ACL check and its explanation. When `trace`
+    /// is given, every rule looked at is appended to it.
+    fn evaluate(
+        &self,
+        dto: &MqttAclDTO,
+        mut trace: Option<&mut Vec<EvaluatedAclRuleDTO>>,
+    ) -> Result<AclOutcome, MqttServiceError> {
         let mqtt = match self.repo.get_by_username(&dto.username)? {
             Some(u) => u,
             None => {
                 debug!("[Service | CheckMQTTACL] User MQTT not found: {}", dto.username);
-                return Err(MqttServiceError::MqttNotFound("User MQTT not found".into()));
+                return Ok(AclOutcome::new(self.settings.unknown_user, AclMatchSource::UnknownUser));
             }
         };
 
         if mqtt.is_deleted {
             debug!("[Service | CheckMQTTACL] User MQTT is deleted or inactive: {}", dto.username);
-            return Err(MqttServiceError::MqttNotActive("User MQTT is not active or deleted".into()));
+            return Ok(AclOutcome::new(AclDecision::Deny, AclMatchSource::DeletedUser));
+        }
+
+        if !peer_allowed(&mqtt.allowed_networks, dto.peerhost.as_deref()) {
+            debug!(
+                "[Service | CheckMQTTACL] Peer address {:?} not in allowed networks → access denied for user `{}`",
+                dto.peerhost, dto.username
+            );
+            return Ok(AclOutcome::new(AclDecision::Deny, AclMatchSource::PeerNetwork));
         }
 
         if mqtt.is_superuser {
@@ -34,22 +150,258 @@ impl MqttAclService {
                 "[Service | CheckMQTTACL] Superuser `{}` → access granted",
                 dto.username
             );
-            return Ok(true);
+            return Ok(AclOutcome::new(AclDecision::Allow, AclMatchSource::Superuser));
         }
 
-        if !dto.topic.starts_with(&dto.username) {
+        // A shared subscription receives the messages of the filter inside it.
+        let shared = parse_shared_subscription(&dto.topic);
+        let topic = shared.as_ref().map_or(dto.topic.as_str(), |s| s.filter);
+        if let Some(protected) = self.protected_topic(topic) {
             debug!(
-            "[Service | CheckMQTTACL] Topic `{}` does not start with username `{}` → access denied",
-            dto.topic, dto.username
-        );
-            return Ok(false);
+                "[Service | CheckMQTTACL] Topic `{}` overlaps protected topic `{}` → access denied for user `{}`",
+                dto.topic, protected, dto.username
+            );
+            return Ok(AclOutcome::new(AclDecision::Deny, AclMatchSource::ProtectedTopic));
+        }
+
+        if let Some(group) = shared.map(|s| s.group).filter(|g| !self.is_share_group_allowed(g)) {
+            debug!(
+                "[Service | CheckMQTTACL] Share group `{}` not allowed → access denied for user `{}`",
+                group, dto.username
+            );
+            return Ok(AclOutcome::new(AclDecision::Deny, AclMatchSource::ShareGroup));
+        }
+
+        let now = Utc::now().with_timezone(&self.settings.timezone);
+        let mut changes_at: Option<i64> = None;
+        for (source, rules) in self.rule_sets(&dto.username)? {
+            for rule in rules {
+                let (expanded_topic, mismatch) = Self::match_rule(&rule, dto, topic, &now);
+                changes_at = changes_at.into_iter().chain(Self::next_time_change(&rule, &now)).min();
+                if let Some(trace) = trace.as_deref_mut() {
+                    trace.push(EvaluatedAclRuleDTO {
+                        source: source.as_str(),
+                        group: source.group().map(str::to_string),
+                        rule: AclRuleService::to_dto(&rule),
+                        expanded_topic,
+                        matched: mismatch.is_none(),
+                        reason: mismatch,
+                    });
+                }
+                if mismatch.is_some() {
+                    continue;
+                }
+
+                let decision = match rule.permission {
+                    AclPermission::Allow => AclDecision::Allow,
+                    AclPermission::Deny => AclDecision::Deny,
+                };
+                debug!(
+                    "[Service | CheckMQTTACL] Rule {} (`{}`, {}) matched {} on topic `{}` for user `{}` → {}",
+                    rule.id, rule.topic, source.as_str(), Self::describe_action(dto.action), dto.topic, dto.username, decision.as_str()
+                );
+                return Ok(AclOutcome { decision, source, rule: Some(rule), changes_at });
+            }
+        }
+
+        if self.is_owned_topic(dto, topic) {
+            debug!(
+                "[Service | CheckMQTTACL] ACL check passed for user `{}` on topic `{}`",
+                dto.username, dto.topic
+            );
+            return Ok(AclOutcome { changes_at, ..AclOutcome::new(AclDecision::Allow, AclMatchSource::Namespace) });
         }
 
         debug!(
-            "[Service | CheckMQTTACL] ACL check passed for user `{}` on topic `{}`",
-            dto.username, dto.topic
+            "[Service | CheckMQTTACL] No rule matched topic `{}` for user `{}` → {}",
+            dto.topic, dto.username, self.settings.no_match.as_str()
         );
-        Ok(true)
+        Ok(AclOutcome { changes_at, ..AclOutcome::new(self.settings.no_match, AclMatchSource::DefaultPolicy) })
+    }
+
+    /// Express the rules applied by `check_acl_permission` as an EMQX JWT `acl` claim.
+    /// The claim format only lists allowed filters per action, so allow rules are included up
+    /// to the first deny rule; anything after it is left to the HTTP ACL check. Allow rules
+    /// limited by QoS, retain or time, or templated on the client id or peer host, cannot be
+    /// expressed and are skipped, and so is a namespace templated on them. The user's own
+    /// namespace is only included when no deny rule precedes it. Filters that overlap a
+    /// protected topic are left out, since the claim cannot carry the protected list's denial.
+    pub fn build_acl_claim(&self, mqtt: &MqttEntity) -> Result<AclClaim, MqttServiceError> {
+        if mqtt.is_superuser {
+            return Ok(AclClaim { all: vec!["#".to_string()], ..Default::default() });
+        }
+
+        let rule_sets = self.rule_sets(&mqtt.username)?;
+        let rules: Vec<&AclRuleEntity> = rule_sets.iter().flat_map(|(_, rules)| rules).collect();
+        let allowed: Vec<&AclRuleEntity> = rules
+            .iter()
+            .copied()
+            .take_while(|r| r.permission == AclPermission::Allow)
+            .collect();
+
+        // `${clientid}` and `${peerhost}` are only known once the client connects.
+        let resolve_username = |placeholder: &str| (placeholder == "${username}").then_some(mqtt.username.as_str());
+        let mut claim = AclClaim::default();
+        for rule in allowed.iter().filter(|r| Self::is_claimable(r)) {
+            let Some(topic) = expand_placeholders(&rule.topic, resolve_username, false) else {
+                continue;
+            };
+            if self.protected_topic(&topic).is_some() {
+                debug!("[Service | CheckMQTTACL] Rule `{}` overlaps a protected topic → left out of ACL claim", rule.topic);
+                continue;
+            }
+            let list = match rule.action {
+                AclAction::All => &mut claim.all,
+                AclAction::Publish => &mut claim.publish,
+                AclAction::Subscribe => &mut claim.subscribe,
+            };
+            list.push(topic);
+        }
+
+        if allowed.len() < rules.len() {
+            debug!("[Service | CheckMQTTACL] Deny rule for `{}` → ACL claim limited to preceding allow rules", mqtt.username);
+        } else if let Some(namespace) = expand_placeholders(&self.settings.namespace, resolve_username, false)
+            .filter(|namespace| self.protected_topic(namespace).is_none())
+        {
+            claim.all.push(namespace);
+        } else {
+            debug!("[Service | CheckMQTTACL] Namespace of `{}` cannot be expressed or overlaps a protected topic → left out of ACL claim", mqtt.username);
+        }
+
+        Ok(claim)
+    }
+
+    /// The rule lists that apply to `username`, in precedence order: the user's own rules,
+    /// then the rules of each group in membership order.
+    fn rule_sets(&self, username: &str) -> Result<Vec<(AclMatchSource, Vec<AclRuleEntity>)>, MqttServiceError> {
+        let mut sets = vec![(AclMatchSource::UserRule, self.repo_rule.get_rules(username)?)];
+        for group in self.repo_group.get_user_groups(username)? {
+            let rules = self.repo_rule.get_group_rules(&group)?;
+            sets.push((AclMatchSource::GroupRule(group), rules));
+        }
+        Ok(sets)
+    }
+
+    /// Match `rule` against `topic`, the request's topic with any share prefix removed,
+    /// returning the expanded topic filter and, when the rule does not apply, why. Attributes
+    /// the caller did not send only satisfy deny rules, so a client that omits `action`, `qos`,
+    /// `retain`, or a value a placeholder needs can never gain access through a rule
+    /// restricted to them.
+    fn match_rule(
+        rule: &AclRuleEntity,
+        dto: &MqttAclDTO,
+        topic: &str,
+        now: &DateTime<Tz>,
+    ) -> (Option<String>, Option<&'static str>) {
+        let unknown = rule.permission == AclPermission::Deny;
+        let resolve = |placeholder: &str| Self::placeholder_value(dto, placeholder);
+        let Some(filter) = expand_placeholders(&rule.topic, resolve, unknown) else {
+            return (None, Some("placeholder value missing or not a single topic level"));
+        };
+        if !topic_matches(&filter, topic) {
+            return (Some(filter), Some("topic does not match"));
+        }
+
+        let action_matches = match (rule.action, dto.action) {
+            (AclAction::All, _) => true,
+            (_, None) => unknown,
+            (AclAction::Publish, Some(action)) => action == MqttAclAction::Publish,
+            (AclAction::Subscribe, Some(action)) => action == MqttAclAction::Subscribe,
+        };
+        let mismatch = if !action_matches {
+            Some("action does not match")
+        } else if !(rule.qos.is_empty() || dto.qos.map_or(unknown, |q| rule.qos.contains(&q))) {
+            Some("qos not covered by rule")
+        } else if !rule.retain.is_none_or(|r| dto.retain.map_or(unknown, |flag| flag == r)) {
+            Some("retain flag does not match")
+        } else {
+            Self::inactive_reason(rule, now)
+        };
+        (Some(filter), mismatch)
+    }
+
+    /// Whether `rule` holds regardless of QoS, retain flag and time, as a claim entry must.
+    fn is_claimable(rule: &AclRuleEntity) -> bool {
+        let validity = &rule.validity;
+        rule.qos.is_empty()
+            && rule.retain.is_none()
+            && validity.valid_from.is_none()
+            && validity.valid_until.is_none()
+            && validity.schedule.is_none()
+    }
+
+    /// Why `rule` is not in effect at `now`, if it is not.
+    fn inactive_reason(rule: &AclRuleEntity, now: &DateTime<Tz>) -> Option<&'static str> {
+        let validity = &rule.validity;
+        let timestamp = now.timestamp();
+        if validity.valid_from.is_some_and(|t| timestamp < t) || validity.valid_until.is_some_and(|t| timestamp >= t) {
+            return Some("outside of validity period");
+        }
+
+        let schedule = validity.schedule.as_ref()?;
+        let day = now.weekday().num_days_from_monday() as u8;
+        let minute = (now.hour() * 60 + now.minute()) as u16;
+        let active = if schedule.start < schedule.end {
+            schedule.days.contains(&day) && (schedule.start..schedule.end).contains(&minute)
+        } else {
+            // Overnight window: the evening of a listed day or the early hours of the day after.
+            (schedule.days.contains(&day) && minute >= schedule.start)
+                || (schedule.days.contains(&((day + 6) % 7)) && minute < schedule.end)
+        };
+        (!active).then_some("outside of schedule")
+    }
+
+    /// Earliest time after `now` at which the rule's validity period or schedule could switch
+    /// it on or off. Schedules have minute resolution and every timezone offset is a whole
+    /// number of minutes, so a schedule can only change at the start of a minute.
+    fn next_time_change(rule: &AclRuleEntity, now: &DateTime<Tz>) -> Option<i64> {
+        let timestamp = now.timestamp();
+        let validity = &rule.validity;
+        let next_minute = validity.schedule.as_ref().map(|_| timestamp - timestamp.rem_euclid(60) + 60);
+        [validity.valid_from, validity.valid_until]
+            .into_iter()
+            .flatten()
+            .filter(|t| *t > timestamp)
+            .chain(next_minute)
+            .min()
+    }
+
+    fn describe_action(action: Option<MqttAclAction>) -> &'static str {
+        match action {
+            Some(MqttAclAction::Publish) => "publish",
+            Some(MqttAclAction::Subscribe) => "subscribe",
+            None => "access",
+        }
+    }
+
+    /// The first protected filter that `topic` could reach. Overlap rather than coverage is
+    /// checked, so a broad subscription such as `$SYS/#` is caught by a protected `$SYS/brokers/#`.
+    fn protected_topic(&self, topic: &str) -> Option<&str> {
+        self.settings
+            .protected_topics
+            .iter()
+            .find(|protected| topics_overlap(protected, topic))
+            .map(String::as_str)
+    }
+
+    fn placeholder_value<'a>(dto: &'a MqttAclDTO, placeholder: &str) -> Option<&'a str> {
+        match placeholder {
+            "${username}" => Some(dto.username.as_str()),
+            "${clientid}" => dto.clientid.as_deref(),
+            "${peerhost}" => dto.peerhost.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn is_owned_topic(&self, dto: &MqttAclDTO, topic: &str) -> bool {
+        let resolve = |placeholder: &str| Self::placeholder_value(dto, placeholder);
+        namespace_covers(&self.settings.namespace, resolve, topic)
+    }
+
+    fn is_share_group_allowed(&self, group: &str) -> bool {
+        let listed = |groups: &[String]| groups.iter().any(|g| g == group);
+        !listed(&self.settings.share_groups_deny)
+            && (self.settings.share_groups_allow.is_empty() || listed(&self.settings.share_groups_allow))
     }
 
     fn mqtt_input_acl_validation(&self, dto: &MqttAclDTO) -> Result<bool, MqttServiceError> {
@@ -68,6 +420,13 @@ impl MqttAclService {
             });
         }
 
+        if dto.qos.is_some_and(|q| q > 2) {
+            errors.push(ValidationError {
+                field: "qos".to_string(),
+                message: "qos must be 0, 1 or 2".to_string(),
+            });
+        }
+
         if !errors.is_empty() {
             return Err(MqttServiceError::BadRequest(errors));
         }
@@ -75,4 +434,109 @@ impl MqttAclService {
         debug!("[Service | CheckMQTTActive] User MQTT input validation passed.");
         Ok(true)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::entities::acl_rule_entity::{AclScheduleEntity, AclValidityEntity};
+
+    fn rule(validity: AclValidityEntity) -> AclRuleEntity {
+        AclRuleEntity::create("r1", AclPermission::Allow, "sensors/#", AclAction::All, vec![], None, validity)
+    }
+
+    fn scheduled(days: Vec<u8>, start: u16, end: u16) -> AclRuleEntity {
+        rule(AclValidityEntity { schedule: Some(AclScheduleEntity { days, start, end }), ..Default::default() })
+    }
+
+    fn at(tz: Tz, y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Tz> {
+        tz.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    // 2024-01-01 is a Monday.
+    #[test]
+    fn daytime_window_only_on_listed_days() {
+        let rule = scheduled(vec![0, 1, 2, 3, 4], 9 * 60, 17 * 60);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 1, 9, 0)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 1, 16, 59)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 1, 17, 0)), Some("outside of schedule"));
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 1, 8, 59)), Some("outside of schedule"));
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 6, 12, 0)), Some("outside of schedule"));
+    }
+
+    #[test]
+    fn overnight_window_rolls_into_next_day() {
+        // Fridays 22:00 until Saturday 06:00.
+        let rule = scheduled(vec![4], 22 * 60, 6 * 60);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 5, 23, 0)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 6, 5, 59)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 6, 6, 0)), Some("outside of schedule"));
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 6, 23, 0)), Some("outside of schedule"));
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 5, 5, 0)), Some("outside of schedule"));
+    }
+
+    #[test]
+    fn overnight_window_wraps_from_sunday_to_monday() {
+        let rule = scheduled(vec![6], 22 * 60, 2 * 60);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 7, 22, 30)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 8, 1, 30)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 7, 1, 30)), Some("outside of schedule"));
+    }
+
+    #[test]
+    fn equal_start_and_end_spans_the_whole_day() {
+        let rule = scheduled(vec![0], 8 * 60, 8 * 60);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 1, 12, 0)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 2, 7, 59)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &at(Tz::UTC, 2024, 1, 2, 8, 0)), Some("outside of schedule"));
+    }
+
+    #[test]
+    fn schedule_uses_local_time_of_the_timezone() {
+        // Monday 09:00-17:00 in Berlin is 08:00-16:00 UTC in winter.
+        let rule = scheduled(vec![0], 9 * 60, 17 * 60);
+        let berlin = |h| Utc.with_ymd_and_hms(2024, 1, 1, h, 30, 0).unwrap().with_timezone(&Tz::Europe__Berlin);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &berlin(8)), None);
+        assert_eq!(MqttAclService::inactive_reason(&rule, &berlin(16)), Some("outside of schedule"));
+
+        // Monday 01:00 UTC is still Sunday evening in New York.
+        let sunday_evening = scheduled(vec![6], 18 * 60, 23 * 60);
+        let new_york = Utc.with_ymd_and_hms(2024, 1, 8, 1, 0, 0).unwrap().with_timezone(&Tz::America__New_York);
+        assert_eq!(MqttAclService::inactive_reason(&sunday_evening, &new_york), None);
+    }
+
+    #[test]
+    fn validity_period_excludes_valid_until() {
+        let now = at(Tz::UTC, 2024, 1, 1, 12, 0);
+        let t = now.timestamp();
+        let active = rule(AclValidityEntity { valid_from: Some(t), valid_until: Some(t + 1), ..Default::default() });
+        let pending = rule(AclValidityEntity { valid_from: Some(t + 1), ..Default::default() });
+        let expired = rule(AclValidityEntity { valid_until: Some(t), ..Default::default() });
+        assert_eq!(MqttAclService::inactive_reason(&active, &now), None);
+        assert_eq!(MqttAclService::inactive_reason(&pending, &now), Some("outside of validity period"));
+        assert_eq!(MqttAclService::inactive_reason(&expired, &now), Some("outside of validity period"));
+    }
+
+    #[test]
+    fn next_time_change_picks_earliest_boundary() {
+        let now = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 12, 0, 30).unwrap();
+        let t = now.timestamp();
+        let next_minute = t + 30;
+
+        assert_eq!(MqttAclService::next_time_change(&rule(AclValidityEntity::default()), &now), None);
+        assert_eq!(MqttAclService::next_time_change(&scheduled(vec![0], 0, 60), &now), Some(next_minute));
+
+        let bounded = rule(AclValidityEntity { valid_from: Some(t - 10), valid_until: Some(t + 100), ..Default::default() });
+        assert_eq!(MqttAclService::next_time_change(&bounded, &now), Some(t + 100));
+
+        let pending = rule(AclValidityEntity { valid_from: Some(t + 5), valid_until: Some(t + 100), ..Default::default() });
+        assert_eq!(MqttAclService::next_time_change(&pending, &now), Some(t + 5));
+
+        let both = rule(AclValidityEntity {
+            valid_until: Some(t + 100),
+            schedule: Some(AclScheduleEntity { days: vec![0], start: 0, end: 60 }),
+            ..Default::default()
+        });
+        assert_eq!(MqttAclService::next_time_change(&both, &now), Some(next_minute));
+    }
+}
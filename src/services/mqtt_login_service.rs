@@ -1,25 +1,55 @@
 use std::sync::Arc;
-use log::debug;
+use log::{debug, warn};
+use crate::entities::mqtt_entity::MqttEntity;
 use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::update_mqtt_repository::UpdateMqttRepository;
+use crate::services::client_id_service::ClientIdService;
+use crate::services::mqtt_acl_service::MqttAclService;
+use crate::services::refresh_token_service::RefreshTokenService;
+use crate::services::token_revocation_service::TokenRevocationService;
 use crate::services::service_error::{MqttServiceError, ValidationError};
-use crate::dtos::mqtt_dto::{AuthType, MqttLoginDTO};
-use crate::utils::hash_password::verify_password;
-use crate::utils::jwt_sign::create_jwt;
+use crate::dtos::mqtt_dto::{AuthType, MqttJwtDTO, MqttLoginDTO, MqttLoginResultDTO};
+use crate::utils::cidr::peer_allowed;
+use crate::utils::hash_password::{hash_password, needs_rehash, verify_password};
+use crate::utils::jwt_keys::KeyRing;
+use crate::utils::jwt_sign::{create_jwt, verify_jwt, JwtSettings};
+use crate::utils::user_lock::UserLocks;
 
 pub struct MqttLoginService {
-    repo: Arc<GetMqttByUsernameRepository>,
-    secret_key: String,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    repo_update: Arc<UpdateMqttRepository>,
+    key_ring: Arc<KeyRing>,
+    jwt_settings: Arc<JwtSettings>,
+    acl_service: Arc<MqttAclService>,
+    refresh_token_service: Arc<RefreshTokenService>,
+    token_revocation_service: Arc<TokenRevocationService>,
+    client_id_service: Arc<ClientIdService>,
+    user_locks: Arc<UserLocks>,
 }
 
 impl MqttLoginService {
-    pub fn new(repo: Arc<GetMqttByUsernameRepository>, secret_key: String) -> Self {
-        Self { repo, secret_key }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        repo_update: Arc<UpdateMqttRepository>,
+        key_ring: Arc<KeyRing>,
+        jwt_settings: Arc<JwtSettings>,
+        acl_service: Arc<MqttAclService>,
+        refresh_token_service: Arc<RefreshTokenService>,
+        token_revocation_service: Arc<TokenRevocationService>,
+        client_id_service: Arc<ClientIdService>,
+        user_locks: Arc<UserLocks>,
+    ) -> Self {
+        Self {
+            repo_get, repo_update, key_ring, jwt_settings, acl_service, refresh_token_service, token_revocation_service,
+            client_id_service, user_locks,
+        }
     }
 
-    pub fn login_with_credentials(&self, dto: MqttLoginDTO) -> Result<(bool, String), MqttServiceError> {
+    pub fn login_with_credentials(&self, dto: MqttLoginDTO) -> Result<MqttLoginResultDTO, MqttServiceError> {
         self.mqtt_input_credentials_validation(&dto)?;
 
-        let mqtt = match self.repo.get_by_username(&dto.username)? {
+        let mqtt = match self.repo_get.get_by_username(&dto.username)? {
             Some(u) => u,
             None => {
                 debug!("[Service | CheckMQTTActive] User MQTT not found: {}", dto.username);
@@ -32,22 +62,103 @@ impl MqttLoginService {
             return Err(MqttServiceError::MqttNotActive("User MQTT is not active or deleted".into()));
         }
 
-        match dto.method.unwrap() {
+        let is_superuser = mqtt.is_superuser;
+        let jwt = match dto.method.as_ref().unwrap() {
             AuthType::Credentials => {
-                let is_valid = verify_password(&dto.password, &mqtt.password);
-                if !is_valid {
-                    debug!("[Service | CheckMQTTActive] Invalid credentials for user MQTT: {}", dto.username);
-                    return Err(MqttServiceError::InvalidCredentials("Invalid credentials".into()));
-                }
-
-                Ok((true, String::new()))
+                self.verify_credentials(&mqtt, &dto.password)?;
+                self.verify_connection(&mqtt, &dto)?;
+                None
             }
             AuthType::Jwt => {
-                let token = create_jwt(&dto.username, &self.secret_key)
+                self.verify_credentials(&mqtt, &dto.password)?;
+                self.verify_connection(&mqtt, &dto)?;
+                let acl = self.jwt_settings.embed_acl.then(|| self.acl_service.build_acl_claim(&mqtt)).transpose()?;
+                let token = create_jwt(&dto.username, &self.key_ring.active(), &self.jwt_settings, dto.ttl, acl)
                     .map_err(|e| MqttServiceError::JwtError(e.to_string()))?;
+                let refresh_token = self.refresh_token_service.issue_refresh_token(&dto.username)?;
                 debug!("[Service | CheckMQTTActive] JWT token created for user MQTT: {}", dto.username);
-                Ok((true, token))
+                Some(MqttJwtDTO { token, refresh_token: Some(refresh_token) })
+            }
+            AuthType::JwtVerify => {
+                let claims = match verify_jwt(&dto.password, &self.key_ring, &self.jwt_settings) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        debug!("[Service | CheckMQTTActive] Invalid JWT for user MQTT {}: {}", dto.username, e);
+                        return Err(MqttServiceError::InvalidCredentials("Invalid or expired token".into()));
+                    }
+                };
+
+                if claims.username != dto.username {
+                    debug!("[Service | CheckMQTTActive] JWT username `{}` does not match user MQTT `{}`", claims.username, dto.username);
+                    return Err(MqttServiceError::InvalidCredentials("Invalid or expired token".into()));
+                }
+
+                if self.token_revocation_service.is_revoked(&claims)? {
+                    debug!("[Service | CheckMQTTActive] JWT {} is revoked for user MQTT: {}", claims.jti, dto.username);
+                    return Err(MqttServiceError::InvalidCredentials("Token has been revoked".into()));
+                }
+
+                self.verify_connection(&mqtt, &dto)?;
+                debug!("[Service | CheckMQTTActive] JWT verified for user MQTT: {}", dto.username);
+                None
+            }
+        };
+        Ok(MqttLoginResultDTO { is_superuser, jwt })
+    }
+
+    /// Checks on the connection EMQX is authenticating: the peer address, then the client ID,
+    /// which may bind on first use and so only runs once everything else has passed.
+    fn verify_connection(&self, mqtt: &MqttEntity, dto: &MqttLoginDTO) -> Result<(), MqttServiceError> {
+        if !peer_allowed(&mqtt.allowed_networks, dto.peerhost.as_deref()) {
+            debug!("[Service | CheckMQTTActive] Peer address {:?} is not allowed for user MQTT: {}", dto.peerhost, mqtt.username);
+            return Err(MqttServiceError::InvalidCredentials("Peer address is not allowed for this user".into()));
+        }
+        self.client_id_service.verify_client_id(mqtt, dto.clientid.as_deref())
+    }
+
+    fn verify_credentials(&self, mqtt: &MqttEntity, password: &str) -> Result<(), MqttServiceError> {
+        if !verify_password(password, &mqtt.password) {
+            debug!("[Service | CheckMQTTActive] Invalid credentials for user MQTT: {}", mqtt.username);
+            return Err(MqttServiceError::InvalidCredentials("Invalid credentials".into()));
+        }
+
+        if needs_rehash(&mqtt.password) {
+            self.upgrade_password_hash(mqtt, password);
+        }
+
+        Ok(())
+    }
+
+    /// Replace a legacy SHA-256 hash with Argon2id after the plaintext has been verified.
+    /// Failures are logged only; the login itself already succeeded and the upgrade is retried next time.
+    fn upgrade_password_hash(&self, mqtt: &MqttEntity, password: &str) {
+        let hashed = match hash_password(password) {
+            Ok(h) => h,
+            Err(e) => {
+                warn!("[Service | CheckMQTTActive] Failed to rehash password for user MQTT {}: {}", mqtt.username, e);
+                return;
+            }
+        };
+
+        // Re-read under the user's lock and change only the hash, so edits saved since the
+        // login read the record are kept.
+        let _guard = self.user_locks.lock(&mqtt.username);
+        let mut current = match self.repo_get.get_by_username(&mqtt.username) {
+            Ok(Some(current)) if !current.is_deleted && current.password == mqtt.password => current,
+            Ok(_) => {
+                debug!("[Service | CheckMQTTActive] User MQTT {} changed during login, skipping hash upgrade", mqtt.username);
+                return;
+            }
+            Err(e) => {
+                warn!("[Service | CheckMQTTActive] Failed to re-read user MQTT {} for hash upgrade: {}", mqtt.username, e);
+                return;
             }
+        };
+
+        current.password = hashed;
+        match self.repo_update.update_mqtt(&current) {
+            Ok(_) => debug!("[Service | CheckMQTTActive] Legacy password hash upgraded to Argon2id for user MQTT: {}", mqtt.username),
+            Err(e) => warn!("[Service | CheckMQTTActive] Failed to persist upgraded password hash for user MQTT {}: {}", mqtt.username, e),
         }
     }
 
@@ -71,10 +182,21 @@ impl MqttLoginService {
             }
         };
 
-        if matches!(method, AuthType::Credentials) && dto.password.trim().is_empty() {
+        if dto.password.trim().is_empty() {
+            let message = match method {
+                AuthType::JwtVerify => "Token is required for JWT verification",
+                _ => "Password is required for credentials login",
+            };
             errors.push(ValidationError {
                 field: "password".into(),
-                message: "Password is required for credentials login".into(),
+                message: message.into(),
+            });
+        }
+
+        if dto.ttl == Some(0) {
+            errors.push(ValidationError {
+                field: "ttl".into(),
+                message: "ttl must be greater than zero".into(),
             });
         }
 
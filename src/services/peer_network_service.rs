@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use log::debug;
+use crate::dtos::mqtt_dto::MqttNetworksDTO;
+use crate::entities::mqtt_entity::MqttEntity;
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::update_mqtt_repository::UpdateMqttRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::acl_cache::AclDecisionCache;
+use crate::utils::cidr::Cidr;
+use crate::utils::user_lock::UserLocks;
+
+pub struct PeerNetworkService {
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    repo_update: Arc<UpdateMqttRepository>,
+    acl_cache: Arc<AclDecisionCache>,
+    user_locks: Arc<UserLocks>,
+}
+
+impl PeerNetworkService {
+    pub fn new(
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        repo_update: Arc<UpdateMqttRepository>,
+        acl_cache: Arc<AclDecisionCache>,
+        user_locks: Arc<UserLocks>,
+    ) -> Self {
+        Self { repo_get, repo_update, acl_cache, user_locks }
+    }
+
+    pub fn get_networks(&self, username: &str) -> Result<MqttNetworksDTO, MqttServiceError> {
+        let mqtt = self.get_user(username)?;
+        Ok(MqttNetworksDTO { networks: mqtt.allowed_networks })
+    }
+
+    /// Replace the networks the user may connect from, stored in normalized CIDR notation.
+    pub fn set_networks(&self, username: &str, dto: MqttNetworksDTO) -> Result<MqttNetworksDTO, MqttServiceError> {
+        let networks = Self::networks_validation(dto.networks)?;
+
+        let _guard = self.user_locks.lock(username);
+        let mut mqtt = self.get_user(username)?;
+        mqtt.allowed_networks = networks;
+        self.repo_update.update_mqtt(&mqtt)?;
+        self.acl_cache.invalidate_user(username);
+        debug!("[Service | PeerNetwork] User MQTT {} now allows {} networks", username, mqtt.allowed_networks.len());
+        Ok(MqttNetworksDTO { networks: mqtt.allowed_networks })
+    }
+
+    fn networks_validation(networks: Vec<String>) -> Result<Vec<String>, MqttServiceError> {
+        let mut errors = Vec::new();
+        let mut normalized: Vec<String> = Vec::with_capacity(networks.len());
+        for (i, network) in networks.iter().enumerate() {
+            match Cidr::parse(network) {
+                Ok(cidr) => {
+                    let cidr = cidr.to_string();
+                    if !normalized.contains(&cidr) {
+                        normalized.push(cidr);
+                    }
+                }
+                Err(message) => errors.push(ValidationError {
+                    field: format!("networks[{}]", i),
+                    message,
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+        Ok(normalized)
+    }
+
+    fn get_user(&self, username: &str) -> Result<MqttEntity, MqttServiceError> {
+        match self.repo_get.get_by_username(username)? {
+            Some(mqtt) => Ok(mqtt),
+            None => {
+                debug!("[Service | PeerNetwork] User MQTT not found: {}", username);
+                Err(MqttServiceError::MqttNotFound("User MQTT not found".into()))
+            }
+        }
+    }
+}
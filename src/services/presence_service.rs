@@ -0,0 +1,259 @@
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use log::debug;
+use crate::dtos::presence_dto::{MqttEventDTO, PresenceDTO};
+use crate::entities::presence_entity::PresenceEntity;
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::presence_repository::PresenceRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+
+/// Reported times below this are Unix seconds (EMQX 4 webhooks), above it milliseconds (EMQX 5).
+/// 10^11 seconds is beyond the year 5000, and 10^11 milliseconds is in 1973.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Disconnect reasons EMQX reports for a connection replaced by a newer one with the same client ID.
+const SUPERSEDED_REASONS: [&str; 2] = ["takenover", "discarded"];
+
+#[derive(Clone, Copy)]
+enum MqttEventType {
+    Connected,
+    Disconnected,
+    SessionTerminated,
+}
+
+pub struct PresenceService {
+    repo_presence: Arc<PresenceRepository>,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    // Serializes read-modify-write of presence records; events for one user can arrive concurrently.
+    write_lock: Mutex<()>,
+}
+
+impl PresenceService {
+    pub fn new(repo_presence: Arc<PresenceRepository>, repo_get: Arc<GetMqttByUsernameRepository>) -> Self {
+        Self { repo_presence, repo_get, write_lock: Mutex::new(()) }
+    }
+
+    /// Apply a client event to the user's presence. Returns `false` when the event was ignored:
+    /// it names no known user, or it is about a connection that has since been replaced.
+    pub fn record_event(&self, dto: MqttEventDTO) -> Result<bool, MqttServiceError> {
+        let event = Self::event_validation(&dto)?;
+        let clientid = dto.clientid.as_deref().unwrap_or_default();
+
+        let Some(username) = dto.username.as_deref().filter(|u| !u.is_empty()) else {
+            debug!("[Service | Presence] Ignoring {} event without username for client {}", dto.event, clientid);
+            return Ok(false);
+        };
+        if self.repo_get.get_by_username(username)?.is_none() {
+            debug!("[Service | Presence] Ignoring {} event for unknown user MQTT: {}", dto.event, username);
+            return Ok(false);
+        }
+
+        let at = Self::event_time(&dto, event);
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut presence = self.repo_presence.get_presence(username)?
+            .unwrap_or_else(|| PresenceEntity::create(username, clientid, at));
+
+        if Self::is_stale(&presence, &dto, event, at) {
+            debug!("[Service | Presence] Ignoring stale {} event for user MQTT {} (client {})", dto.event, username, clientid);
+            return Ok(false);
+        }
+
+        match event {
+            MqttEventType::Connected => {
+                presence.online = true;
+                presence.clientid = clientid.to_string();
+                presence.peer = dto.peername;
+                presence.proto_ver = dto.proto_ver;
+                presence.connected_at = Some(at);
+                presence.disconnected_at = None;
+                presence.disconnect_reason = None;
+                presence.session_terminated_at = None;
+            }
+            MqttEventType::Disconnected => {
+                presence.online = false;
+                presence.disconnected_at = Some(at);
+                presence.disconnect_reason = dto.reason;
+            }
+            MqttEventType::SessionTerminated => {
+                presence.online = false;
+                presence.disconnected_at.get_or_insert(at);
+                presence.session_terminated_at = Some(at);
+            }
+        }
+        presence.last_seen = presence.last_seen.max(at);
+
+        self.repo_presence.save_presence(&presence)?;
+        debug!("[Service | Presence] User MQTT {} is now {}", username, if presence.online { "online" } else { "offline" });
+        Ok(true)
+    }
+
+    pub fn get_presence(&self, username: &str) -> Result<PresenceDTO, MqttServiceError> {
+        match self.repo_presence.get_presence(username)? {
+            Some(presence) => Ok(Self::to_dto(presence)),
+            None => {
+                debug!("[Service | Presence] No presence recorded for user MQTT: {}", username);
+                Err(MqttServiceError::MqttNotFound("No presence recorded for user MQTT".into()))
+            }
+        }
+    }
+
+    pub fn get_presence_list(&self) -> Result<Vec<PresenceDTO>, MqttServiceError> {
+        let presences = self.repo_presence.get_presence_list()?;
+        debug!("[Service | Presence] {} presence records retrieved successfully.", presences.len());
+        Ok(presences.into_iter().map(Self::to_dto).collect())
+    }
+
+    pub fn get_online_list(&self) -> Result<Vec<PresenceDTO>, MqttServiceError> {
+        let presences = self.repo_presence.get_presence_list()?;
+        Ok(presences.into_iter().filter(|p| p.online).map(Self::to_dto).collect())
+    }
+
+    /// Users last seen at least `hours` ago and not online since, longest offline first.
+    pub fn get_offline_list(&self, hours: Option<u64>) -> Result<Vec<PresenceDTO>, MqttServiceError> {
+        let Some(hours) = hours else {
+            return Err(MqttServiceError::BadRequest(vec![ValidationError {
+                field: "hours".to_string(),
+                message: "hours is required".to_string(),
+            }]));
+        };
+
+        let cutoff = Utc::now().timestamp().saturating_sub(i64::try_from(hours.saturating_mul(3600)).unwrap_or(i64::MAX));
+        let mut presences: Vec<PresenceEntity> = self.repo_presence.get_presence_list()?
+            .into_iter()
+            .filter(|p| !p.online && p.last_seen <= cutoff)
+            .collect();
+        presences.sort_by_key(|p| p.last_seen);
+        debug!("[Service | Presence] {} users offline for at least {} hours.", presences.len(), hours);
+        Ok(presences.into_iter().map(Self::to_dto).collect())
+    }
+
+    fn event_validation(dto: &MqttEventDTO) -> Result<MqttEventType, MqttServiceError> {
+        let mut errors = Vec::new();
+        // EMQX 4 webhooks name events `client_connected`; rule-engine events use dots.
+        let event = match dto.event.replace('_', ".").as_str() {
+            "client.connected" => Some(MqttEventType::Connected),
+            "client.disconnected" => Some(MqttEventType::Disconnected),
+            "session.terminated" => Some(MqttEventType::SessionTerminated),
+            _ => {
+                errors.push(ValidationError {
+                    field: "event".to_string(),
+                    message: "event must be one of client.connected, client.disconnected, session.terminated".to_string(),
+                });
+                None
+            }
+        };
+
+        if dto.clientid.as_deref().is_none_or(str::is_empty) {
+            errors.push(ValidationError {
+                field: "clientid".to_string(),
+                message: "clientid cannot be empty".to_string(),
+            });
+        }
+
+        match event {
+            Some(event) if errors.is_empty() => Ok(event),
+            _ => Err(MqttServiceError::BadRequest(errors)),
+        }
+    }
+
+    /// When the event happened, in Unix seconds, preferring the time EMQX reports.
+    fn event_time(dto: &MqttEventDTO, event: MqttEventType) -> i64 {
+        let reported = match event {
+            MqttEventType::Connected => dto.connected_at,
+            MqttEventType::Disconnected => dto.disconnected_at,
+            MqttEventType::SessionTerminated => None,
+        };
+        reported.or(dto.timestamp).map_or_else(|| Utc::now().timestamp(), Self::to_unix_seconds)
+    }
+
+    fn to_unix_seconds(reported: i64) -> i64 {
+        if reported.abs() < MILLIS_THRESHOLD { reported } else { reported / 1000 }
+    }
+
+    /// Whether the event is about an older connection than the one on record, for example the
+    /// disconnect of a session another connection took over. Webhooks may also arrive out of order.
+    fn is_stale(presence: &PresenceEntity, dto: &MqttEventDTO, event: MqttEventType, at: i64) -> bool {
+        let current_connection = presence.connected_at.unwrap_or(i64::MIN);
+        match event {
+            MqttEventType::Connected => at < current_connection,
+            MqttEventType::Disconnected | MqttEventType::SessionTerminated => {
+                let connected_at = dto.connected_at.map(Self::to_unix_seconds).unwrap_or(at);
+                dto.clientid.as_deref() != Some(presence.clientid.as_str())
+                    || connected_at < current_connection
+                    || dto.reason.as_deref().is_some_and(|r| SUPERSEDED_REASONS.contains(&r))
+            }
+        }
+    }
+
+    fn to_dto(presence: PresenceEntity) -> PresenceDTO {
+        PresenceDTO {
+            username: presence.username,
+            online: presence.online,
+            clientid: presence.clientid,
+            peer: presence.peer,
+            proto_ver: presence.proto_ver,
+            connected_at: presence.connected_at,
+            disconnected_at: presence.disconnected_at,
+            disconnect_reason: presence.disconnect_reason,
+            session_terminated_at: presence.session_terminated_at,
+            last_seen: presence.last_seen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, connected_at: Option<i64>, disconnected_at: Option<i64>) -> MqttEventDTO {
+        MqttEventDTO {
+            event: event.to_string(),
+            username: Some("sensor_001".to_string()),
+            clientid: Some("c1".to_string()),
+            peername: None,
+            proto_ver: None,
+            reason: None,
+            connected_at,
+            disconnected_at,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn event_time_reads_emqx5_milliseconds() {
+        let dto = event("client.connected", Some(1_767_225_600_123), None);
+        assert_eq!(PresenceService::event_time(&dto, MqttEventType::Connected), 1_767_225_600);
+    }
+
+    #[test]
+    fn event_time_reads_emqx4_seconds() {
+        let dto = event("client_connected", Some(1_767_225_600), None);
+        assert_eq!(PresenceService::event_time(&dto, MqttEventType::Connected), 1_767_225_600);
+    }
+
+    #[test]
+    fn event_time_falls_back_to_timestamp() {
+        let dto = MqttEventDTO { timestamp: Some(1_767_225_600_000), ..event("session.terminated", None, None) };
+        assert_eq!(PresenceService::event_time(&dto, MqttEventType::SessionTerminated), 1_767_225_600);
+    }
+
+    #[test]
+    fn emqx4_disconnect_of_current_connection_is_not_stale() {
+        let mut presence = PresenceEntity::create("sensor_001", "c1", 1_767_225_600);
+        presence.connected_at = Some(1_767_225_600);
+        let dto = event("client_disconnected", Some(1_767_225_600), Some(1_767_229_200));
+        assert!(!PresenceService::is_stale(&presence, &dto, MqttEventType::Disconnected, 1_767_229_200));
+    }
+
+    #[test]
+    fn disconnect_of_replaced_connection_is_stale() {
+        let mut presence = PresenceEntity::create("sensor_001", "c1", 1_767_225_660);
+        presence.connected_at = Some(1_767_225_660);
+        let dto = event("client.disconnected", Some(1_767_225_600_000), Some(1_767_225_660_000));
+        assert!(PresenceService::is_stale(&presence, &dto, MqttEventType::Disconnected, 1_767_225_660));
+
+        let takenover = MqttEventDTO { reason: Some("takenover".to_string()), ..event("client.disconnected", None, None) };
+        presence.connected_at = Some(0);
+        assert!(PresenceService::is_stale(&presence, &takenover, MqttEventType::Disconnected, 1_767_225_660));
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use log::{debug, warn};
+use uuid::Uuid;
+use crate::entities::refresh_token_entity::{RefreshFamilyEntity, RefreshTokenEntity};
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::services::mqtt_acl_service::MqttAclService;
+use crate::services::token_revocation_service::TokenRevocationService;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::dtos::mqtt_dto::{MqttJwtDTO, RefreshTokenDTO};
+use crate::utils::jwt_keys::KeyRing;
+use crate::utils::jwt_sign::{create_jwt, JwtSettings};
+use crate::utils::opaque_token::{generate_opaque_token, hash_opaque_token};
+
+pub struct RefreshTokenService {
+    repo_token: Arc<RefreshTokenRepository>,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    key_ring: Arc<KeyRing>,
+    jwt_settings: Arc<JwtSettings>,
+    acl_service: Arc<MqttAclService>,
+    token_revocation_service: Arc<TokenRevocationService>,
+    // Serializes rotations so one refresh token cannot be exchanged twice concurrently.
+    rotation_lock: Mutex<()>,
+}
+
+impl RefreshTokenService {
+    pub fn new(
+        repo_token: Arc<RefreshTokenRepository>,
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        key_ring: Arc<KeyRing>,
+        jwt_settings: Arc<JwtSettings>,
+        acl_service: Arc<MqttAclService>,
+        token_revocation_service: Arc<TokenRevocationService>,
+    ) -> Self {
+        Self { repo_token, repo_get, key_ring, jwt_settings, acl_service, token_revocation_service, rotation_lock: Mutex::new(()) }
+    }
+
+    /// Start a new token family for a user who just authenticated with their password.
+    pub fn issue_refresh_token(&self, username: &str) -> Result<String, MqttServiceError> {
+        let token = generate_opaque_token();
+        let family = RefreshFamilyEntity::create(Uuid::new_v4().to_string(), username);
+        let entity = self.new_token_entity(&token, username, &family.family_id);
+        self.repo_token.create_family(&family, &entity)?;
+        debug!("[Service | RefreshToken] Refresh token family {} issued for user MQTT: {}", family.family_id, username);
+        Ok(token)
+    }
+
+    /// Exchange a refresh token for a new access/refresh pair. The presented token is
+    /// invalidated; presenting it again revokes every token in its family.
+    pub fn refresh(&self, dto: RefreshTokenDTO) -> Result<MqttJwtDTO, MqttServiceError> {
+        self.refresh_input_validation(&dto)?;
+
+        let _guard = self.rotation_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let token_hash = hash_opaque_token(&dto.refresh_token);
+        let mut current = match self.repo_token.get_token(&token_hash)? {
+            Some(t) => t,
+            None => {
+                debug!("[Service | RefreshToken] Unknown refresh token presented.");
+                return Err(MqttServiceError::InvalidCredentials("Invalid refresh token".into()));
+            }
+        };
+
+        let mut family = match self.repo_token.get_family(&current.family_id)? {
+            Some(f) if !f.is_revoked => f,
+            _ => {
+                debug!("[Service | RefreshToken] Refresh token family {} is revoked.", current.family_id);
+                return Err(MqttServiceError::InvalidCredentials("Invalid refresh token".into()));
+            }
+        };
+
+        if current.is_used {
+            warn!(
+                "[Service | RefreshToken] Reuse of rotated refresh token detected for user MQTT {} → revoking family {}",
+                current.username, family.family_id
+            );
+            family.is_revoked = true;
+            self.repo_token.save_family(&family)?;
+            return Err(MqttServiceError::InvalidCredentials("Refresh token reuse detected".into()));
+        }
+
+        if self.token_revocation_service.is_issued_before_user_revocation(&current.username, current.issued_at_ms)? {
+            debug!("[Service | RefreshToken] Refresh token revoked for user MQTT: {}", current.username);
+            return Err(MqttServiceError::InvalidCredentials("Invalid refresh token".into()));
+        }
+
+        if current.expires_at <= Utc::now().timestamp() {
+            debug!("[Service | RefreshToken] Refresh token expired for user MQTT: {}", current.username);
+            return Err(MqttServiceError::InvalidCredentials("Refresh token expired".into()));
+        }
+
+        let mqtt = match self.repo_get.get_by_username(&current.username)? {
+            Some(u) => u,
+            None => {
+                debug!("[Service | RefreshToken] User MQTT not found: {}", current.username);
+                return Err(MqttServiceError::MqttNotFound("User MQTT not found".into()));
+            }
+        };
+
+        if mqtt.is_deleted {
+            debug!("[Service | RefreshToken] User MQTT is deleted or inactive: {}", current.username);
+            return Err(MqttServiceError::MqttNotActive("User MQTT is not active or deleted".into()));
+        }
+
+        let acl = self.jwt_settings.embed_acl.then(|| self.acl_service.build_acl_claim(&mqtt)).transpose()?;
+        let access_token = create_jwt(&mqtt.username, &self.key_ring.active(), &self.jwt_settings, None, acl)
+            .map_err(|e| MqttServiceError::JwtError(e.to_string()))?;
+
+        let refresh_token = generate_opaque_token();
+        let next = self.new_token_entity(&refresh_token, &mqtt.username, &family.family_id);
+        current.is_used = true;
+        self.repo_token.rotate(&current, &next)?;
+
+        debug!("[Service | RefreshToken] Refresh token rotated for user MQTT: {}", mqtt.username);
+        Ok(MqttJwtDTO { token: access_token, refresh_token: Some(refresh_token) })
+    }
+
+    /// Drop refresh tokens and families that can no longer produce an access token.
+    pub fn prune_expired(&self) -> Result<usize, MqttServiceError> {
+        Ok(self.repo_token.prune_expired(Utc::now().timestamp())?)
+    }
+
+    fn new_token_entity(&self, token: &str, username: &str, family_id: &str) -> RefreshTokenEntity {
+        let now = Utc::now();
+        RefreshTokenEntity::create(
+            hash_opaque_token(token),
+            username,
+            family_id,
+            now.timestamp_millis(),
+            now.timestamp() + self.jwt_settings.refresh_ttl_secs as i64,
+        )
+    }
+
+    fn refresh_input_validation(&self, dto: &RefreshTokenDTO) -> Result<bool, MqttServiceError> {
+        let mut errors = Vec::new();
+        if dto.refresh_token.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "refresh_token".to_string(),
+                message: "refresh_token cannot be empty".to_string(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | RefreshToken] Refresh token input validation passed.");
+        Ok(true)
+    }
+}
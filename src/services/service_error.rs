@@ -30,4 +30,7 @@ pub enum MqttServiceError {
 
     #[error("JWT error: {0}")]
     JwtError(String),
+
+    #[error("Password hash error: {0}")]
+    PasswordHashError(String),
 }
\ No newline at end of file
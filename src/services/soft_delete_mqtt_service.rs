@@ -3,24 +3,34 @@ use log::debug;
 use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
 use crate::repositories::soft_delete_mqtt_repository::SoftDeleteMqttRepository;
 use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::services::token_revocation_service::TokenRevocationService;
+use crate::utils::acl_cache::AclDecisionCache;
+use crate::utils::user_lock::UserLocks;
 
 pub struct SoftDeleteMqttService {
     repo_get: Arc<GetMqttByUsernameRepository>,
-    repo_delete: Arc<SoftDeleteMqttRepository>
+    repo_delete: Arc<SoftDeleteMqttRepository>,
+    token_revocation_service: Arc<TokenRevocationService>,
+    acl_cache: Arc<AclDecisionCache>,
+    user_locks: Arc<UserLocks>,
 }
 
 impl SoftDeleteMqttService {
     pub fn new(
         repo_get: Arc<GetMqttByUsernameRepository>,
-        repo_delete: Arc<SoftDeleteMqttRepository>
+        repo_delete: Arc<SoftDeleteMqttRepository>,
+        token_revocation_service: Arc<TokenRevocationService>,
+        acl_cache: Arc<AclDecisionCache>,
+        user_locks: Arc<UserLocks>,
     ) -> SoftDeleteMqttService {
-        Self { repo_get, repo_delete }
+        Self { repo_get, repo_delete, token_revocation_service, acl_cache, user_locks }
     }
 
     pub fn soft_delete_mqtt(&self, username: &str) -> Result<bool, MqttServiceError> {
         self.validate_username(username)?;
 
-        let mqtt = match self.repo_get.get_by_username(&username)? {
+        let guard = self.user_locks.lock(username);
+        let mqtt = match self.repo_get.get_by_username(username)? {
             Some(u) => u,
             None => {
                 debug!("[Service | SoftDeleteMQTT] User MQTT not found: {}", username);
@@ -34,6 +44,9 @@ impl SoftDeleteMqttService {
         }
 
         self.repo_delete.soft_delete(mqtt)?;
+        drop(guard);
+        self.acl_cache.invalidate_user(username);
+        self.token_revocation_service.revoke_user_tokens(username)?;
         debug!("[Service | SoftDeleteMQTT] Successfully soft deleted user MQTT: {}", username);
         Ok(true)
     }
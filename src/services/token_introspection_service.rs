@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use log::debug;
+use crate::dtos::mqtt_dto::{IntrospectTokenDTO, TokenIntrospectionDTO};
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::services::token_revocation_service::TokenRevocationService;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::jwt_keys::KeyRing;
+use crate::utils::jwt_sign::{verify_jwt, JwtSettings};
+
+pub struct TokenIntrospectionService {
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    key_ring: Arc<KeyRing>,
+    jwt_settings: Arc<JwtSettings>,
+    token_revocation_service: Arc<TokenRevocationService>,
+}
+
+impl TokenIntrospectionService {
+    pub fn new(
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        key_ring: Arc<KeyRing>,
+        jwt_settings: Arc<JwtSettings>,
+        token_revocation_service: Arc<TokenRevocationService>,
+    ) -> Self {
+        Self { repo_get, key_ring, jwt_settings, token_revocation_service }
+    }
+
+    /// A token is active when its signature and time claims verify, it has not been revoked,
+    /// and its user still exists and is not deleted.
+    pub fn introspect(&self, dto: IntrospectTokenDTO) -> Result<TokenIntrospectionDTO, MqttServiceError> {
+        self.introspect_input_validation(&dto)?;
+        if let Some(hint) = dto.token_type_hint.as_deref().filter(|h| *h != "access_token") {
+            debug!("[Service | TokenIntrospection] Ignoring token_type_hint {}; only access tokens are introspected.", hint);
+        }
+
+        let claims = match verify_jwt(&dto.token, &self.key_ring, &self.jwt_settings) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("[Service | TokenIntrospection] Token is not active: {}", e);
+                return Ok(TokenIntrospectionDTO::default());
+            }
+        };
+
+        let mqtt = match self.repo_get.get_by_username(&claims.username)? {
+            Some(u) => u,
+            None => {
+                debug!("[Service | TokenIntrospection] User MQTT not found: {}", claims.username);
+                return Ok(TokenIntrospectionDTO::default());
+            }
+        };
+
+        let is_revoked = self.token_revocation_service.is_revoked(&claims)?;
+        let active = !is_revoked && !mqtt.is_deleted;
+        debug!("[Service | TokenIntrospection] Token {} of user MQTT {} introspected (active={}).", claims.jti, claims.username, active);
+        if !active {
+            return Ok(TokenIntrospectionDTO::default());
+        }
+
+        Ok(TokenIntrospectionDTO {
+            active: true,
+            token_type: Some("access_token".to_string()),
+            username: Some(claims.username),
+            sub: Some(claims.sub),
+            iss: claims.iss,
+            aud: claims.aud,
+            exp: Some(claims.exp),
+            iat: Some(claims.iat),
+            nbf: Some(claims.nbf),
+            jti: Some(claims.jti),
+            is_superuser: Some(mqtt.is_superuser),
+        })
+    }
+
+    fn introspect_input_validation(&self, dto: &IntrospectTokenDTO) -> Result<bool, MqttServiceError> {
+        let mut errors = Vec::new();
+        if dto.token.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "token".to_string(),
+                message: "token cannot be empty".to_string(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | TokenIntrospection] Introspect token input validation passed.");
+        Ok(true)
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+use chrono::Utc;
+use jsonwebtoken::errors::ErrorKind;
+use log::debug;
+use crate::dtos::jwt_dto::Claims;
+use crate::dtos::mqtt_dto::RevokeTokenDTO;
+use crate::entities::revoked_token_entity::{RevokedTokenEntity, UserTokenRevocationEntity};
+use crate::repositories::get_mqtt_by_username_repository::GetMqttByUsernameRepository;
+use crate::repositories::token_revocation_repository::TokenRevocationRepository;
+use crate::services::service_error::{MqttServiceError, ValidationError};
+use crate::utils::jwt_keys::KeyRing;
+use crate::utils::jwt_sign::{verify_jwt, JwtSettings};
+
+pub struct TokenRevocationService {
+    repo_revocation: Arc<TokenRevocationRepository>,
+    repo_get: Arc<GetMqttByUsernameRepository>,
+    key_ring: Arc<KeyRing>,
+    jwt_settings: Arc<JwtSettings>,
+}
+
+impl TokenRevocationService {
+    pub fn new(
+        repo_revocation: Arc<TokenRevocationRepository>,
+        repo_get: Arc<GetMqttByUsernameRepository>,
+        key_ring: Arc<KeyRing>,
+        jwt_settings: Arc<JwtSettings>,
+    ) -> Self {
+        Self { repo_revocation, repo_get, key_ring, jwt_settings }
+    }
+
+    /// Revoke a single access token by its `jti`. Tokens that already expired are accepted as a no-op.
+    pub fn revoke_token(&self, dto: RevokeTokenDTO) -> Result<bool, MqttServiceError> {
+        self.revoke_input_validation(&dto)?;
+
+        let claims = match verify_jwt(&dto.token, &self.key_ring, &self.jwt_settings) {
+            Ok(c) => c,
+            Err(e) if matches!(e.kind(), ErrorKind::ExpiredSignature) => {
+                debug!("[Service | TokenRevocation] Token already expired, nothing to revoke.");
+                return Ok(true);
+            }
+            Err(e) => {
+                debug!("[Service | TokenRevocation] Token rejected for revocation: {}", e);
+                return Err(MqttServiceError::InvalidCredentials("Invalid token".into()));
+            }
+        };
+
+        let revoked = RevokedTokenEntity::create(&claims.jti, &claims.username, claims.exp as i64);
+        self.repo_revocation.revoke_jti(&revoked)?;
+        debug!("[Service | TokenRevocation] Token {} revoked for user MQTT: {}", claims.jti, claims.username);
+        Ok(true)
+    }
+
+    /// Revoke every access and refresh token issued to `username` up to now.
+    pub fn revoke_all_tokens(&self, username: &str) -> Result<bool, MqttServiceError> {
+        self.validate_username(username)?;
+
+        if self.repo_get.get_by_username(username)?.is_none() {
+            debug!("[Service | TokenRevocation] User MQTT not found: {}", username);
+            return Err(MqttServiceError::MqttNotFound("User MQTT not found".into()));
+        }
+
+        self.revoke_user_tokens(username)
+    }
+
+    /// Same as `revoke_all_tokens` for callers that already loaded the user.
+    pub fn revoke_user_tokens(&self, username: &str) -> Result<bool, MqttServiceError> {
+        let revocation = UserTokenRevocationEntity::create(username, Utc::now().timestamp_millis());
+        self.repo_revocation.revoke_user(&revocation)?;
+        debug!("[Service | TokenRevocation] All tokens revoked for user MQTT: {}", username);
+        Ok(true)
+    }
+
+    pub fn is_revoked(&self, claims: &Claims) -> Result<bool, MqttServiceError> {
+        if self.is_issued_before_user_revocation(&claims.username, claims.iat_ms)? {
+            return Ok(true);
+        }
+        Ok(self.repo_revocation.is_jti_revoked(&claims.jti)?)
+    }
+
+    /// Whether a token issued to `username` at `issued_at_ms` falls under a revoke-all. Compared
+    /// in milliseconds, so a login right after the revocation is not caught by it.
+    pub fn is_issued_before_user_revocation(&self, username: &str, issued_at_ms: i64) -> Result<bool, MqttServiceError> {
+        match self.repo_revocation.get_user_revocation(username)? {
+            Some(revocation) => Ok(issued_at_ms < revocation.revoked_before_ms),
+            None => Ok(false),
+        }
+    }
+
+    /// Drop denylist entries that can no longer match a live token.
+    pub fn prune_expired(&self) -> Result<usize, MqttServiceError> {
+        let now = Utc::now().timestamp();
+        let longest_lifetime = self.jwt_settings.max_ttl_secs.max(self.jwt_settings.refresh_ttl_secs) as i64;
+        Ok(self.repo_revocation.prune_expired(now, (now - longest_lifetime) * 1000)?)
+    }
+
+    fn revoke_input_validation(&self, dto: &RevokeTokenDTO) -> Result<bool, MqttServiceError> {
+        let mut errors = Vec::new();
+        if dto.token.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "token".to_string(),
+                message: "token cannot be empty".to_string(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | TokenRevocation] Revoke token input validation passed.");
+        Ok(true)
+    }
+
+    fn validate_username(&self, username: &str) -> Result<bool, MqttServiceError> {
+        let mut errors = Vec::new();
+        if username.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "username".to_string(),
+                message: "username cannot be empty".to_string(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(MqttServiceError::BadRequest(errors));
+        }
+
+        debug!("[Service | TokenRevocation] Username validation passed.");
+        Ok(true)
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use crate::dtos::acl_dto::AclCacheStatsDTO;
+use crate::dtos::mqtt_dto::{MqttAclAction, MqttAclDTO};
+use crate::utils::acl_policy::{AclDecision, AclMatchSource};
+
+/// Every attribute of an ACL request that the decision can depend on.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AclCacheKey {
+    username: String,
+    clientid: Option<String>,
+    peerhost: Option<String>,
+    action: Option<MqttAclAction>,
+    topic: String,
+    qos: Option<u8>,
+    retain: Option<bool>,
+}
+
+impl AclCacheKey {
+    pub fn new(dto: &MqttAclDTO) -> Self {
+        AclCacheKey {
+            username: dto.username.clone(),
+            clientid: dto.clientid.clone(),
+            peerhost: dto.peerhost.clone(),
+            action: dto.action,
+            topic: dto.topic.clone(),
+            qos: dto.qos,
+            retain: dto.retain,
+        }
+    }
+}
+
+/// Bounded, TTL-limited cache of ACL decisions. Writers invalidate the entries they affect;
+/// a decision computed while an invalidation ran is not stored, so a stale read cannot outlive
+/// the write that made it stale.
+pub struct AclDecisionCache {
+    ttl: Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct CacheState {
+    entries: HashMap<AclCacheKey, CachedDecision>,
+    // Entries ordered by expiry, so expired and soonest-expiring entries are evicted without a
+    // scan. The sequence number keeps slots of entries expiring at the same instant apart.
+    expiry: BTreeMap<ExpirySlot, AclCacheKey>,
+    next_seq: u64,
+    // Bumped by every invalidation; inserts started under an older generation are dropped.
+    generation: u64,
+}
+
+type ExpirySlot = (Instant, u64);
+
+struct CachedDecision {
+    decision: AclDecision,
+    source: AclMatchSource,
+    slot: ExpirySlot,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &AclCacheKey) {
+        if let Some(cached) = self.entries.remove(key) {
+            self.expiry.remove(&cached.slot);
+        }
+    }
+
+    fn pop_soonest(&mut self) {
+        if let Some((_, key)) = self.expiry.pop_first() {
+            self.entries.remove(&key);
+        }
+    }
+
+    fn purge_expired(&mut self, now: Instant) {
+        while self.expiry.first_key_value().is_some_and(|((expires_at, _), _)| *expires_at <= now) {
+            self.pop_soonest();
+        }
+    }
+}
+
+impl AclDecisionCache {
+    /// A zero `ttl` or `capacity` disables the cache.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        AclDecisionCache {
+            ttl,
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                expiry: BTreeMap::new(),
+                next_seq: 0,
+                generation: 0,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.capacity > 0
+    }
+
+    pub fn get(&self, key: &AclCacheKey) -> Option<(AclDecision, AclMatchSource)> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let cached = self.lock().entries
+            .get(key)
+            .filter(|c| c.slot.0 > Instant::now())
+            .map(|c| (c.decision, c.source.clone()));
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    /// Generation to pass to `insert` for a decision about to be computed.
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Store a decision for the cache TTL, or for `valid_for` if that is shorter, e.g. until a
+    /// time-limited rule the decision depends on switches on or off.
+    pub fn insert(&self, key: AclCacheKey, decision: AclDecision, source: AclMatchSource, generation: u64, valid_for: Option<Duration>) {
+        let ttl = valid_for.map_or(self.ttl, |v| v.min(self.ttl));
+        if !self.is_enabled() || ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut state = self.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.remove(&key);
+        state.purge_expired(now);
+        if state.entries.len() >= self.capacity {
+            state.pop_soonest();
+        }
+
+        let slot = (now + ttl, state.next_seq);
+        state.next_seq += 1;
+        state.expiry.insert(slot, key.clone());
+        state.entries.insert(key, CachedDecision { decision, source, slot });
+    }
+
+    /// Drop every decision about `username`.
+    pub fn invalidate_user(&self, username: &str) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.entries.retain(|k, _| k.username != username);
+        state.expiry.retain(|_, k| k.username != username);
+    }
+
+    /// Drop every decision, for changes such as group rules that affect many users.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.entries.clear();
+        state.expiry.clear();
+    }
+
+    pub fn stats(&self) -> AclCacheStatsDTO {
+        AclCacheStatsDTO {
+            enabled: self.is_enabled(),
+            entries: self.lock().entries.len(),
+            capacity: self.capacity,
+            ttl_secs: self.ttl.as_secs(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(username: &str, topic: &str) -> AclCacheKey {
+        AclCacheKey {
+            username: username.to_string(),
+            clientid: None,
+            peerhost: None,
+            action: Some(MqttAclAction::Publish),
+            topic: topic.to_string(),
+            qos: None,
+            retain: None,
+        }
+    }
+
+    fn insert(cache: &AclDecisionCache, key: AclCacheKey, valid_for: Option<Duration>) {
+        cache.insert(key, AclDecision::Allow, AclMatchSource::UserRule, cache.generation(), valid_for);
+    }
+
+    #[test]
+    fn full_cache_evicts_the_soonest_expiring_entry() {
+        let cache = AclDecisionCache::new(Duration::from_secs(60), 2);
+        insert(&cache, key("alice", "a"), None);
+        insert(&cache, key("alice", "b"), Some(Duration::from_secs(10)));
+        insert(&cache, key("alice", "c"), None);
+
+        assert!(cache.get(&key("alice", "a")).is_some());
+        assert!(cache.get(&key("alice", "b")).is_none());
+        assert!(cache.get(&key("alice", "c")).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn reinserting_a_key_does_not_evict_another() {
+        let cache = AclDecisionCache::new(Duration::from_secs(60), 2);
+        insert(&cache, key("alice", "a"), None);
+        insert(&cache, key("alice", "b"), None);
+        insert(&cache, key("alice", "a"), None);
+
+        assert!(cache.get(&key("alice", "a")).is_some());
+        assert!(cache.get(&key("alice", "b")).is_some());
+        assert_eq!(cache.lock().expiry.len(), 2);
+    }
+
+    #[test]
+    fn entries_expire_after_their_ttl() {
+        let cache = AclDecisionCache::new(Duration::from_secs(60), 2);
+        insert(&cache, key("alice", "a"), Some(Duration::from_millis(20)));
+        insert(&cache, key("alice", "b"), None);
+        assert!(cache.get(&key("alice", "a")).is_some());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cache.get(&key("alice", "a")).is_none());
+
+        // The expired entry is purged first, so a new one fits without evicting a live one.
+        insert(&cache, key("alice", "c"), None);
+        assert!(cache.get(&key("alice", "b")).is_some());
+        assert!(cache.get(&key("alice", "c")).is_some());
+    }
+
+    #[test]
+    fn insert_from_before_an_invalidation_is_dropped() {
+        let cache = AclDecisionCache::new(Duration::from_secs(60), 10);
+        let generation = cache.generation();
+        cache.invalidate_user("bob");
+        cache.insert(key("alice", "a"), AclDecision::Allow, AclMatchSource::UserRule, generation, None);
+        assert!(cache.get(&key("alice", "a")).is_none());
+
+        insert(&cache, key("alice", "a"), None);
+        assert!(cache.get(&key("alice", "a")).is_some());
+    }
+
+    #[test]
+    fn invalidate_user_keeps_other_users() {
+        let cache = AclDecisionCache::new(Duration::from_secs(60), 10);
+        insert(&cache, key("alice", "a"), None);
+        insert(&cache, key("bob", "a"), None);
+        cache.invalidate_user("alice");
+
+        assert!(cache.get(&key("alice", "a")).is_none());
+        assert!(cache.get(&key("bob", "a")).is_some());
+        assert_eq!(cache.lock().expiry.len(), 1);
+    }
+}
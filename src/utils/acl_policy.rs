@@ -0,0 +1,181 @@
+use chrono_tz::Tz;
+use crate::utils::topic_filter::{expand_placeholders, topic_matches, validate_placeholders, validate_topic_filter};
+
+/// Outcome of an ACL check, in EMQX's HTTP authorizer vocabulary. `Ignore` hands the
+/// decision to the next authorizer in EMQX's chain.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AclDecision {
+    Allow,
+    Deny,
+    Ignore,
+}
+
+impl AclDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Ignore => "ignore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            "ignore" => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
+
+pub struct AclSettings {
+    /// Answer when no rule and no owned namespace matches the topic.
+    pub no_match: AclDecision,
+    /// Answer for users this service does not know; only `Deny` or `Ignore`.
+    pub unknown_user: AclDecision,
+    /// Topic filter template for the topics every user owns, e.g. `tenants/+/devices/${username}/#`.
+    pub namespace: String,
+    /// Topic filters no non-superuser may publish or subscribe to, whatever their rules say.
+    pub protected_topics: Vec<String>,
+    /// Share groups non-superusers may subscribe through; empty allows any group.
+    pub share_groups_allow: Vec<String>,
+    /// Share groups non-superusers may never subscribe through.
+    pub share_groups_deny: Vec<String>,
+    /// Timezone in which rule schedules are evaluated.
+    pub timezone: Tz,
+}
+
+impl Default for AclSettings {
+    fn default() -> Self {
+        Self {
+            no_match: AclDecision::Deny,
+            unknown_user: AclDecision::Deny,
+            namespace: "${username}/#".to_string(),
+            protected_topics: Vec::new(),
+            share_groups_allow: Vec::new(),
+            share_groups_deny: Vec::new(),
+            timezone: Tz::UTC,
+        }
+    }
+}
+
+/// Check a namespace template: a valid topic filter with one level that is exactly
+/// `${username}`, so no two users can expand it to the same namespace.
+pub fn validate_namespace_template(template: &str) -> Result<(), &'static str> {
+    validate_topic_filter(template)?;
+    validate_placeholders(template)?;
+    if !template.split('/').any(|level| level == "${username}") {
+        return Err("a level must consist of ${username} alone");
+    }
+    Ok(())
+}
+
+/// Whether `topic` lies in the namespace `template` expands to. Levels are compared whole, so
+/// `dev1` owns `dev1/...` but not `dev10/...` or `dev1-admin/...`.
+pub fn namespace_covers<'a>(template: &str, resolve: impl Fn(&str) -> Option<&'a str>, topic: &str) -> bool {
+    expand_placeholders(template, resolve, false).is_some_and(|namespace| topic_matches(&namespace, topic))
+}
+
+/// Parse a comma-separated list of protected topic filters.
+pub fn parse_protected_topics(value: &str) -> Result<Vec<String>, &'static str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(|filter| validate_topic_filter(filter).map(|()| filter.to_string()))
+        .collect()
+}
+
+/// Parse a comma-separated list of share group names.
+pub fn parse_share_groups(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|group| !group.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// What decided an ACL check.
+#[derive(Clone, PartialEq, Debug)]
+pub enum AclMatchSource {
+    Superuser,
+    UserRule,
+    GroupRule(String),
+    Namespace,
+    ProtectedTopic,
+    ShareGroup,
+    DefaultPolicy,
+    UnknownUser,
+    DeletedUser,
+    PeerNetwork,
+}
+
+impl AclMatchSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Superuser => "superuser",
+            Self::UserRule => "user_rule",
+            Self::GroupRule(_) => "group_rule",
+            Self::Namespace => "namespace",
+            Self::ProtectedTopic => "protected_topic",
+            Self::ShareGroup => "share_group",
+            Self::DefaultPolicy => "default_policy",
+            Self::UnknownUser => "unknown_user",
+            Self::DeletedUser => "deleted_user",
+            Self::PeerNetwork => "peer_network",
+        }
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        match self {
+            Self::GroupRule(group) => Some(group),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn username<'a>(value: &'a str) -> impl Fn(&str) -> Option<&'a str> {
+        move |placeholder| (placeholder == "${username}").then_some(value)
+    }
+
+    #[test]
+    fn default_namespace_is_owned_by_level() {
+        let namespace = AclSettings::default().namespace;
+        assert!(namespace_covers(&namespace, username("dev1"), "dev1/state"));
+        assert!(namespace_covers(&namespace, username("dev1"), "dev1"));
+        assert!(!namespace_covers(&namespace, username("dev1"), "dev10/state"));
+        assert!(!namespace_covers(&namespace, username("dev1"), "dev1-admin/state"));
+    }
+
+    #[test]
+    fn templated_namespace_is_owned_by_level() {
+        let namespace = "tenants/+/devices/${username}/#";
+        assert!(namespace_covers(namespace, username("dev1"), "tenants/acme/devices/dev1/state"));
+        assert!(!namespace_covers(namespace, username("dev1"), "tenants/acme/devices/dev10/state"));
+        assert!(!namespace_covers(namespace, username("dev1"), "tenants/acme/dev1/state"));
+    }
+
+    #[test]
+    fn username_that_changes_the_filter_owns_nothing() {
+        let namespace = AclSettings::default().namespace;
+        assert!(!namespace_covers(&namespace, username("+"), "dev1/state"));
+        assert!(!namespace_covers(&namespace, username("#"), "dev1/state"));
+        assert!(!namespace_covers(&namespace, username("dev1/state"), "dev1/state"));
+    }
+
+    #[test]
+    fn namespace_template_needs_a_username_level() {
+        assert!(validate_namespace_template("${username}/#").is_ok());
+        assert!(validate_namespace_template("tenants/+/devices/${username}/#").is_ok());
+        assert!(validate_namespace_template("devices/#").is_err());
+        assert!(validate_namespace_template("devices/user-${username}/#").is_err());
+        assert!(validate_namespace_template("devices/${clientid}/#").is_err());
+        assert!(validate_namespace_template("${username}/#/state").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::net::IpAddr;
+
+/// An IPv4 or IPv6 network in CIDR notation. A bare address is a single-host network.
+#[derive(Clone, Copy)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Parse `10.0.0.0/8`, `2001:db8::/32` or a bare address. Host bits are cleared, so
+    /// `10.1.2.3/8` becomes `10.0.0.0/8`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value.trim(), None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| format!("`{}` is not an IP address", addr))?;
+        let max = max_prefix(&addr);
+        let prefix = match prefix {
+            Some(p) => p.parse::<u8>().ok().filter(|p| *p <= max)
+                .ok_or_else(|| format!("prefix length must be between 0 and {}", max))?,
+            None => max,
+        };
+        Ok(Cidr { network: mask(addr, prefix), prefix })
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        let addr = canonical(*addr);
+        addr.is_ipv4() == self.network.is_ipv4() && mask(addr, self.prefix) == self.network
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Whether `peerhost` lies in one of `networks`. An empty list allows any peer; otherwise a
+/// missing or unparsable peer address is refused.
+pub fn peer_allowed(networks: &[String], peerhost: Option<&str>) -> bool {
+    if networks.is_empty() {
+        return true;
+    }
+    let Some(addr) = peerhost.and_then(|p| p.trim().parse::<IpAddr>().ok()) else {
+        return false;
+    };
+    networks.iter().filter_map(|n| Cidr::parse(n).ok()).any(|n| n.contains(&addr))
+}
+
+fn max_prefix(addr: &IpAddr) -> u8 {
+    if addr.is_ipv4() { 32 } else { 128 }
+}
+
+// EMQX reports IPv4 clients on dual-stack listeners as `::ffff:a.b.c.d`.
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        v4 => v4,
+    }
+}
+
+fn mask(addr: IpAddr, prefix: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(v4) => {
+            let bits = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            IpAddr::V4((u32::from(v4) & bits).into())
+        }
+        IpAddr::V6(v6) => {
+            let bits = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            IpAddr::V6((u128::from(v6) & bits).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_clears_host_bits() {
+        assert_eq!(Cidr::parse("10.1.2.3/8").unwrap().to_string(), "10.0.0.0/8");
+        assert_eq!(Cidr::parse("2001:db8::1/32").unwrap().to_string(), "2001:db8::/32");
+        assert_eq!(Cidr::parse(" 192.168.1.10 ").unwrap().to_string(), "192.168.1.10/32");
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("10.0.0.0/x").is_err());
+        assert!(Cidr::parse("example.com/8").is_err());
+    }
+
+    #[test]
+    fn host_network_contains_only_that_address() {
+        let host = Cidr::parse("192.168.1.10/32").unwrap();
+        assert!(host.contains(&addr("192.168.1.10")));
+        assert!(!host.contains(&addr("192.168.1.11")));
+    }
+
+    #[test]
+    fn zero_prefix_contains_its_whole_family() {
+        let any_v4 = Cidr::parse("0.0.0.0/0").unwrap();
+        assert!(any_v4.contains(&addr("203.0.113.7")));
+        assert!(!any_v4.contains(&addr("2001:db8::1")));
+
+        let any_v6 = Cidr::parse("::/0").unwrap();
+        assert!(any_v6.contains(&addr("2001:db8::1")));
+        assert!(!any_v6.contains(&addr("203.0.113.7")));
+    }
+
+    #[test]
+    fn ipv4_mapped_ipv6_peer_matches_ipv4_network() {
+        let network = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(network.contains(&addr("::ffff:10.1.2.3")));
+        assert!(!network.contains(&addr("::ffff:11.1.2.3")));
+        assert!(!Cidr::parse("::/0").unwrap().contains(&addr("::ffff:10.1.2.3")));
+    }
+
+    #[test]
+    fn peer_allowed_refuses_unknown_peers_only_when_restricted() {
+        let networks = vec!["10.0.0.0/8".to_string()];
+        assert!(peer_allowed(&[], None));
+        assert!(peer_allowed(&networks, Some("10.1.2.3")));
+        assert!(!peer_allowed(&networks, Some("192.168.1.10")));
+        assert!(!peer_allowed(&networks, Some("not-an-ip")));
+        assert!(!peer_allowed(&networks, None));
+    }
+}
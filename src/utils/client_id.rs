@@ -0,0 +1,93 @@
+use regex::Regex;
+use crate::entities::mqtt_entity::ClientIdPatternEntity;
+
+/// Compile a client-ID regex anchored at both ends, so it must match the whole ID.
+pub fn compile_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+pub fn client_id_matches(pattern: &ClientIdPatternEntity, client_id: &str) -> bool {
+    match pattern {
+        ClientIdPatternEntity::Exact(p) => p == client_id,
+        ClientIdPatternEntity::Glob(p) => glob_matches(p, client_id),
+        // Patterns are validated when stored; one that no longer compiles matches nothing.
+        ClientIdPatternEntity::Regex(p) => compile_regex(p).is_ok_and(|r| r.is_match(client_id)),
+    }
+}
+
+/// `*` matches any run of characters, `?` exactly one.
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // Position after the last `*` and the value index it was tried at, for backtracking.
+    let mut star: Option<(usize, usize)> = None;
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, v));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match star {
+                Some((after_star, tried)) => {
+                    p = after_star;
+                    v = tried + 1;
+                    star = Some((after_star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_star_and_question_mark() {
+        assert!(glob_matches("sensor-*", "sensor-001"));
+        assert!(glob_matches("sensor-*", "sensor-"));
+        assert!(glob_matches("sensor-???", "sensor-001"));
+        assert!(!glob_matches("sensor-???", "sensor-01"));
+        assert!(glob_matches("*", "anything"));
+    }
+
+    #[test]
+    fn glob_backtracks_over_multiple_stars() {
+        assert!(glob_matches("*-*-prod", "eu-west-1-prod"));
+        assert!(glob_matches("a*b*c", "abxbyc"));
+        assert!(glob_matches("a*b?c", "abbbxc"));
+        assert!(!glob_matches("a*b*c", "abxbyd"));
+    }
+
+    #[test]
+    fn glob_trailing_stars_and_empty_input() {
+        assert!(glob_matches("dev**", "dev"));
+        assert!(glob_matches("", ""));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("?", ""));
+        assert!(!glob_matches("", "x"));
+    }
+
+    #[test]
+    fn glob_non_match() {
+        assert!(!glob_matches("sensor-*", "actuator-001"));
+        assert!(!glob_matches("sensor", "sensor-001"));
+    }
+
+    #[test]
+    fn pattern_kinds() {
+        assert!(client_id_matches(&ClientIdPatternEntity::Exact("c1".to_string()), "c1"));
+        assert!(!client_id_matches(&ClientIdPatternEntity::Exact("c*".to_string()), "c1"));
+        assert!(client_id_matches(&ClientIdPatternEntity::Regex("c\\d+".to_string()), "c12"));
+        assert!(!client_id_matches(&ClientIdPatternEntity::Regex("c\\d+".to_string()), "xc12"));
+    }
+}
@@ -1,17 +1,81 @@
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::{SaltString, rand_core::OsRng, Error as PasswordHashError};
 use sha2::{Sha256, Digest};
 use log::debug;
 use subtle::ConstantTimeEq;
 
-pub fn hash_password(password: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(password.as_bytes());
-    let result = hasher.finalize();
-    debug!("[Utils] Password hashed using SHA-256.");
-    hex::encode(result)
+const ARGON2ID_PREFIX: &str = "$argon2id$";
+
+/// Hash a password with Argon2id and return it as a PHC string
+/// (`$argon2id$v=19$m=...,t=...,p=...$<salt>$<hash>`).
+pub fn hash_password(password: &str) -> Result<String, PasswordHashError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+    debug!("[Utils] Password hashed using Argon2id.");
+    Ok(hash.to_string())
 }
 
+/// Verify a password against either an Argon2 PHC string or a legacy
+/// unsalted hex SHA-256 hash.
 pub fn verify_password(password: &str, stored_hash: &str) -> bool {
-    let hashed_input = hash_password(password);
+    let is_valid = if stored_hash.starts_with("$argon2") {
+        match PasswordHash::new(stored_hash) {
+            Ok(parsed) => Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok(),
+            Err(e) => {
+                debug!("[Utils] Stored Argon2 hash could not be parsed: {}", e);
+                false
+            }
+        }
+    } else {
+        let hashed_input = legacy_sha256_hash(password);
+        hashed_input.as_bytes().ct_eq(stored_hash.as_bytes()).into()
+    };
     debug!("[Utils] Password verification completed.");
-    hashed_input.as_bytes().ct_eq(stored_hash.as_bytes()).into()
+    is_valid
+}
+
+/// Whether a stored hash predates Argon2id and should be replaced on the next successful login.
+pub fn needs_rehash(stored_hash: &str) -> bool {
+    !stored_hash.starts_with(ARGON2ID_PREFIX)
+}
+
+fn legacy_sha256_hash(password: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(password.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of `Passw0rd!`, as stored before Argon2id.
+    const LEGACY_HASH: &str = "e66860546f18cdbbcd86b35e18b525bffc67f772c650cedfe3ff7a0026fa1dee";
+
+    #[test]
+    fn argon2id_hash_verifies_only_its_password() {
+        let hash = hash_password("Passw0rd!").unwrap();
+        assert!(hash.starts_with(ARGON2ID_PREFIX));
+        assert!(verify_password("Passw0rd!", &hash));
+        assert!(!verify_password("passw0rd!", &hash));
+        assert!(!needs_rehash(&hash));
+    }
+
+    #[test]
+    fn same_password_gets_a_fresh_salt() {
+        assert_ne!(hash_password("Passw0rd!").unwrap(), hash_password("Passw0rd!").unwrap());
+    }
+
+    #[test]
+    fn legacy_sha256_hash_verifies_and_needs_rehash() {
+        assert!(verify_password("Passw0rd!", LEGACY_HASH));
+        assert!(!verify_password("passw0rd!", LEGACY_HASH));
+        assert!(needs_rehash(LEGACY_HASH));
+    }
+
+    #[test]
+    fn malformed_argon2_hash_never_verifies() {
+        assert!(!verify_password("Passw0rd!", "$argon2id$v=19$garbage"));
+        assert!(!verify_password("", ""));
+    }
 }
@@ -1,15 +1,127 @@
-use jsonwebtoken::{encode, Header, EncodingKey, errors::Error as JwtError};
+use jsonwebtoken::{encode, decode, decode_header, Header, Validation};
+use jsonwebtoken::errors::{Error as JwtError, ErrorKind};
 use chrono::{Utc, Duration};
-use crate::dtos::jwt_dto::Claims;
+use uuid::Uuid;
+use crate::dtos::jwt_dto::{AclClaim, Claims};
+use crate::utils::jwt_keys::{JwtKey, KeyRing};
+use crate::utils::topic_filter::substitute_placeholders;
 
-pub fn create_jwt(username: &str, secret: &str) -> Result<String, JwtError> {
+/// Registered claims and lifetimes applied to every issued token.
+pub struct JwtSettings {
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    /// `sub` claim; `${username}` is replaced with the token owner.
+    pub subject: String,
+    pub default_ttl_secs: u64,
+    pub max_ttl_secs: u64,
+    pub refresh_ttl_secs: u64,
+    /// Embed an EMQX `acl` claim so the broker can authorize from the token alone.
+    pub embed_acl: bool,
+}
+
+impl Default for JwtSettings {
+    fn default() -> Self {
+        JwtSettings {
+            issuer: None,
+            audience: None,
+            subject: "IoTNet".to_string(),
+            default_ttl_secs: 3600,
+            max_ttl_secs: 86400,
+            refresh_ttl_secs: 30 * 86400,
+            embed_acl: false,
+        }
+    }
+}
+
+impl JwtSettings {
+    /// Check a `sub` template: `${username}` is the only placeholder a token subject can use.
+    pub fn validate_subject(subject: &str) -> Result<(), &'static str> {
+        match substitute_placeholders(subject, Self::resolve_username("")) {
+            Some(_) => Ok(()),
+            None => Err("only the ${username} placeholder is supported"),
+        }
+    }
+
+    /// The `sub` claim for `username`.
+    pub fn subject_for(&self, username: &str) -> String {
+        substitute_placeholders(&self.subject, Self::resolve_username(username)).unwrap_or_else(|| self.subject.clone())
+    }
+
+    fn resolve_username<'a>(username: &'a str) -> impl Fn(&str) -> Option<&'a str> {
+        move |placeholder| (placeholder == "${username}").then_some(username)
+    }
+
+    /// Requested TTL, or the default one, capped at the server maximum.
+    pub fn effective_ttl(&self, requested_secs: Option<u64>) -> u64 {
+        requested_secs.unwrap_or(self.default_ttl_secs).min(self.max_ttl_secs)
+    }
+}
+
+pub fn create_jwt(username: &str, key: &JwtKey, settings: &JwtSettings, ttl_secs: Option<u64>, acl: Option<AclClaim>) -> Result<String, JwtError> {
     let now = Utc::now();
+    let ttl = Duration::seconds(settings.effective_ttl(ttl_secs) as i64);
     let claims = Claims {
         username: username.to_string(),
-        exp: (now + Duration::hours(1)).timestamp() as usize,
+        exp: (now + ttl).timestamp() as usize,
         iat: now.timestamp() as usize,
-        sub: "IoTNet".parse().unwrap(),
+        iat_ms: now.timestamp_millis(),
+        nbf: now.timestamp() as usize,
+        sub: settings.subject_for(username),
+        jti: Uuid::new_v4().to_string(),
+        iss: settings.issuer.clone(),
+        aud: settings.audience.clone(),
+        acl,
     };
 
-    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref()))
-}
\ No newline at end of file
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+    encode(&header, &claims, key.encoding_key())
+}
+
+/// Validate signature, expiry, `nbf` and the configured issuer/audience of a token
+/// issued by `create_jwt` and return its claims. The verification key is selected by
+/// the header `kid`; tokens without one are checked against the active key.
+pub fn verify_jwt(token: &str, key_ring: &KeyRing, settings: &JwtSettings) -> Result<Claims, JwtError> {
+    let header = decode_header(token)?;
+    let key = match header.kid {
+        Some(kid) => key_ring
+            .find(&kid, Utc::now().timestamp())
+            .ok_or_else(|| JwtError::from(ErrorKind::InvalidSignature))?,
+        None => key_ring.active(),
+    };
+
+    let mut validation = Validation::new(key.algorithm);
+    validation.validate_nbf = true;
+    validation.set_required_spec_claims(&["exp", "nbf", "sub"]);
+    if let Some(issuer) = &settings.issuer {
+        validation.set_issuer(&[issuer]);
+    }
+    match &settings.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+
+    let data = decode::<Claims>(token, key.decoding_key(), &validation)?;
+    Ok(data.claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subject_expands_username_as_is() {
+        let settings = JwtSettings { subject: "users/${username}".to_string(), ..Default::default() };
+        assert_eq!(settings.subject_for("dev1"), "users/dev1");
+        assert_eq!(settings.subject_for("site/a+b"), "users/site/a+b");
+        assert_eq!(JwtSettings::default().subject_for("dev1"), "IoTNet");
+    }
+
+    #[test]
+    fn subject_accepts_only_the_username_placeholder() {
+        assert!(JwtSettings::validate_subject("IoTNet").is_ok());
+        assert!(JwtSettings::validate_subject("${username}@IoTNet").is_ok());
+        assert!(JwtSettings::validate_subject("${clientid}").is_err());
+        assert!(JwtSettings::validate_subject("${user}").is_err());
+    }
+}
@@ -1,2 +1,10 @@
 pub mod hash_password;
+pub mod jwt_keys;
 pub mod jwt_sign;
+pub mod opaque_token;
+pub mod topic_filter;
+pub mod acl_policy;
+pub mod acl_cache;
+pub mod client_id;
+pub mod cidr;
+pub mod user_lock;
@@ -0,0 +1,15 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Sha256, Digest};
+
+/// Generate a random 256-bit token, hex encoded.
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Storage key for an opaque token. The token is high-entropy, so a plain SHA-256
+/// is enough to keep the stored value useless to someone reading the database.
+pub fn hash_opaque_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
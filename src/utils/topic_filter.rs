@@ -0,0 +1,258 @@
+/// Check a topic filter against MQTT 3.1.1/5.0 rules: `#` only as the whole last level,
+/// `+` only as a whole level, no NUL characters.
+pub fn validate_topic_filter(filter: &str) -> Result<(), &'static str> {
+    if filter.is_empty() {
+        return Err("topic filter cannot be empty");
+    }
+    if filter.contains('\0') {
+        return Err("topic filter cannot contain NUL characters");
+    }
+
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (i, level) in levels.iter().enumerate() {
+        if level.contains('#') && (*level != "#" || i != levels.len() - 1) {
+            return Err("`#` must occupy the last level on its own");
+        }
+        if level.contains('+') && *level != "+" {
+            return Err("`+` must occupy a whole level");
+        }
+    }
+    Ok(())
+}
+
+/// Whether `filter` covers `topic`. `topic` may itself be a subscription filter, in which
+/// case it only matches when every topic it could receive is covered: a `+` level is only
+/// covered by `+` or `#`, and a `#` level only by `#`. Topics starting with `$` are not
+/// matched by a leading wildcard.
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+
+    let mut filter_levels = filter.split('/');
+    let mut topic_levels = topic.split('/');
+    loop {
+        match (filter_levels.next(), topic_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(level)) if level != "#" => {}
+            (Some(f), Some(t)) if f == t => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Whether some topic could match both filters, e.g. `$SYS/#` and `$SYS/+/clients`. For a
+/// topic name without wildcards this is the same as `topic_matches`.
+pub fn topics_overlap(a: &str, b: &str) -> bool {
+    let leading_wildcard = |f: &str| f.starts_with('+') || f.starts_with('#');
+    if (a.starts_with('$') && leading_wildcard(b)) || (b.starts_with('$') && leading_wildcard(a)) {
+        return false;
+    }
+
+    let mut a_levels = a.split('/');
+    let mut b_levels = b.split('/');
+    loop {
+        match (a_levels.next(), b_levels.next()) {
+            (Some("#"), _) | (_, Some("#")) => return true,
+            (Some("+"), Some(_)) | (Some(_), Some("+")) => {}
+            (Some(x), Some(y)) if x == y => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// A `$share/{group}/{filter}` or `$queue/{filter}` subscription. `$queue` subscriptions
+/// have no group name of their own and report `$queue` as their group.
+pub struct SharedSubscription<'a> {
+    pub group: &'a str,
+    pub filter: &'a str,
+}
+
+/// Split a shared subscription into its group and the filter it actually subscribes to.
+/// Returns `None` for plain topics and malformed shared subscriptions.
+pub fn parse_shared_subscription(topic: &str) -> Option<SharedSubscription<'_>> {
+    if let Some(filter) = topic.strip_prefix("$queue/") {
+        return (!filter.is_empty()).then_some(SharedSubscription { group: "$queue", filter });
+    }
+
+    let (group, filter) = topic.strip_prefix("$share/")?.split_once('/')?;
+    let valid_group = !group.is_empty() && !group.contains(['+', '#']);
+    (valid_group && !filter.is_empty()).then_some(SharedSubscription { group, filter })
+}
+
+/// Placeholders that ACL topic filters may contain, expanded per request.
+pub const PLACEHOLDERS: [&str; 3] = ["${username}", "${clientid}", "${peerhost}"];
+
+/// Reject `${...}` sequences other than the supported placeholders.
+pub fn validate_placeholders(filter: &str) -> Result<(), &'static str> {
+    let mut rest = filter;
+    while let Some(start) = rest.find("${") {
+        let candidate = &rest[start..];
+        match PLACEHOLDERS.iter().find(|p| candidate.starts_with(*p)) {
+            Some(placeholder) => rest = &candidate[placeholder.len()..],
+            None => return Err("only ${username}, ${clientid} and ${peerhost} placeholders are supported"),
+        }
+    }
+    Ok(())
+}
+
+/// Substitute placeholders in `filter` with the values from `resolve`. A value that is
+/// missing, empty, or contains `/`, `+`, `#` or NUL would change the shape of the filter, so
+/// it is unresolved: the whole filter expands to `None`, or with `widen_unresolved` the level
+/// holding it becomes `+` (used for deny rules, which must not be escaped by omitting a value).
+pub fn expand_placeholders<'a>(
+    filter: &str,
+    resolve: impl Fn(&str) -> Option<&'a str>,
+    widen_unresolved: bool,
+) -> Option<String> {
+    if !filter.contains("${") {
+        return Some(filter.to_string());
+    }
+
+    let mut levels = Vec::new();
+    for level in filter.split('/') {
+        match expand_level(level, &resolve) {
+            Some(expanded) => levels.push(expanded),
+            None if widen_unresolved => levels.push("+".to_string()),
+            None => return None,
+        }
+    }
+    Some(levels.join("/"))
+}
+
+fn expand_level<'a>(level: &str, resolve: &impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+    substitute_placeholders(level, |placeholder| resolve(placeholder).filter(|v| is_safe_value(v)))
+}
+
+/// Substitute placeholders in `text` with the values from `resolve`, or `None` when one of them
+/// is unsupported or has no value. Single left-to-right pass, so a substituted value is never
+/// scanned for placeholders again.
+pub fn substitute_placeholders<'a>(text: &str, resolve: impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let placeholder = PLACEHOLDERS.iter().find(|p| candidate.starts_with(*p))?;
+        expanded.push_str(resolve(placeholder)?);
+        rest = &candidate[placeholder.len()..];
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+
+fn is_safe_value(value: &str) -> bool {
+    !value.is_empty() && !value.contains(['/', '+', '#', '\0'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_match_whole_levels() {
+        assert!(topic_matches("sensors/+/temp", "sensors/dev1/temp"));
+        assert!(topic_matches("sensors/#", "sensors/dev1/temp"));
+        assert!(topic_matches("sensors/#", "sensors"));
+        assert!(!topic_matches("sensors/+", "sensors/dev1/temp"));
+        assert!(!topic_matches("sensors/dev1", "sensors/dev1/temp"));
+    }
+
+    #[test]
+    fn levels_are_compared_whole() {
+        assert!(topic_matches("devices/dev1/#", "devices/dev1/state"));
+        assert!(!topic_matches("devices/dev1/#", "devices/dev10/state"));
+        assert!(!topic_matches("devices/dev1/#", "devices/dev1-admin/state"));
+    }
+
+    #[test]
+    fn leading_wildcards_skip_dollar_topics() {
+        assert!(!topic_matches("#", "$SYS/brokers"));
+        assert!(!topic_matches("+/brokers", "$SYS/brokers"));
+        assert!(topic_matches("$SYS/#", "$SYS/brokers"));
+    }
+
+    #[test]
+    fn wildcard_topic_is_matched_only_by_a_wider_filter() {
+        assert!(topic_matches("sensors/+", "sensors/+"));
+        assert!(topic_matches("sensors/#", "sensors/+"));
+        assert!(topic_matches("sensors/#", "sensors/#"));
+        assert!(!topic_matches("sensors/+", "sensors/#"));
+        assert!(!topic_matches("sensors/dev1", "sensors/+"));
+        assert!(!topic_matches("+/temp", "sensors/+"));
+    }
+
+    #[test]
+    fn filters_are_validated_per_level() {
+        assert!(validate_topic_filter("sensors/+/temp/#").is_ok());
+        assert!(validate_topic_filter("").is_err());
+        assert!(validate_topic_filter("sensors/#/temp").is_err());
+        assert!(validate_topic_filter("sensors/dev+").is_err());
+        assert!(validate_topic_filter("sensors/\0").is_err());
+    }
+
+    fn resolve_with<'a>(username: &'a str, clientid: Option<&'a str>) -> impl Fn(&str) -> Option<&'a str> {
+        move |placeholder| match placeholder {
+            "${username}" => Some(username),
+            "${clientid}" => clientid,
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn placeholders_expand_within_a_level() {
+        let resolve = resolve_with("dev1", Some("c1"));
+        assert_eq!(expand_placeholders("devices/${username}/#", &resolve, false).as_deref(), Some("devices/dev1/#"));
+        assert_eq!(expand_placeholders("${username}-${clientid}/state", &resolve, false).as_deref(), Some("dev1-c1/state"));
+        assert_eq!(expand_placeholders("devices/+/state", &resolve, false).as_deref(), Some("devices/+/state"));
+    }
+
+    #[test]
+    fn values_that_change_the_filter_shape_are_unresolved() {
+        for username in ["a/b", "+", "#", "dev+1", ""] {
+            let resolve = resolve_with(username, None);
+            assert_eq!(expand_placeholders("devices/${username}/#", &resolve, false), None, "{:?}", username);
+            assert_eq!(expand_placeholders("devices/${username}/#", &resolve, true).as_deref(), Some("devices/+/#"));
+        }
+    }
+
+    #[test]
+    fn missing_value_is_unresolved() {
+        let resolve = resolve_with("dev1", None);
+        assert_eq!(expand_placeholders("clients/${clientid}/cmd", &resolve, false), None);
+        assert_eq!(expand_placeholders("clients/${clientid}/cmd", &resolve, true).as_deref(), Some("clients/+/cmd"));
+    }
+
+    #[test]
+    fn substituted_values_are_not_expanded_again() {
+        let resolve = resolve_with("${clientid}", Some("c1"));
+        assert_eq!(expand_placeholders("devices/${username}", &resolve, false).as_deref(), Some("devices/${clientid}"));
+    }
+
+    #[test]
+    fn only_supported_placeholders_validate() {
+        assert!(validate_placeholders("devices/${username}/${clientid}/${peerhost}").is_ok());
+        assert!(validate_placeholders("$SYS/#").is_ok());
+        assert!(validate_placeholders("devices/${user}/#").is_err());
+        assert!(validate_placeholders("devices/${username/#").is_err());
+    }
+
+    #[test]
+    fn overlap_is_symmetric_across_wildcards() {
+        assert!(topics_overlap("sensors/+/temp", "sensors/dev1/+"));
+        assert!(topics_overlap("sensors/dev1/+", "sensors/+/temp"));
+        assert!(topics_overlap("sensors/#", "+/dev1"));
+        assert!(!topics_overlap("sensors/+", "sensors/dev1/temp"));
+        assert!(!topics_overlap("sensors/dev1", "sensors/dev2"));
+        assert!(!topics_overlap("devices/dev1/#", "devices/dev10/#"));
+    }
+
+    #[test]
+    fn overlap_keeps_dollar_topics_apart_from_leading_wildcards() {
+        assert!(!topics_overlap("#", "$SYS/+"));
+        assert!(!topics_overlap("$SYS/+", "+/brokers"));
+        assert!(topics_overlap("$SYS/#", "$SYS/+/clients"));
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard};
+
+const STRIPES: usize = 64;
+
+/// Locks shared by every service that reads a `mqtt:{username}` record and writes it back
+/// whole, so concurrent edits of one user cannot overwrite each other. Usernames are hashed
+/// onto a fixed set of stripes: unrelated users may share a lock, one user never has two.
+pub struct UserLocks {
+    stripes: Vec<Mutex<()>>,
+}
+
+impl UserLocks {
+    pub fn new() -> Self {
+        UserLocks { stripes: (0..STRIPES).map(|_| Mutex::new(())).collect() }
+    }
+
+    pub fn lock(&self, username: &str) -> MutexGuard<'_, ()> {
+        let mut hasher = DefaultHasher::new();
+        username.hash(&mut hasher);
+        let stripe = &self.stripes[hasher.finish() as usize % self.stripes.len()];
+        stripe.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for UserLocks {
+    fn default() -> Self {
+        Self::new()
+    }
+}